@@ -0,0 +1,256 @@
+use std::{collections::HashMap, fmt::Display, fs, hint::black_box, io, time::{Duration, Instant}};
+
+use crate::{days::Day, error::ParseError, input::InputSource, solution::Solution};
+
+pub const DEFAULT_RUNS: usize = 10;
+/// A median that got slower than its baseline by more than this is reported as a regression.
+const REGRESSION_THRESHOLD: f64 = 0.1;
+/// Slowdowns smaller than this are considered noise, no matter how large they are relatively.
+const REGRESSION_MIN_DURATION: Duration = Duration::from_micros(100);
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Phase {
+    Parse,
+    Part(u8)
+}
+
+impl Phase {
+    /// Name of the phase in baseline files.
+    fn key(self) -> String {
+        match self {
+            Phase::Parse => String::from("parse"),
+            Phase::Part(part) => format!("part_{part}")
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Phase> {
+        match key {
+            "parse" => Some(Phase::Parse),
+            "part_1" => Some(Phase::Part(1)),
+            "part_2" => Some(Phase::Part(2)),
+            _ => None
+        }
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part {part}")
+        }
+    }
+}
+
+/// Measured durations of each phase, one per run.
+pub type Samples = Vec<(Phase, Vec<Duration>)>;
+
+/// Times parsing the input and solving the requested parts, each phase `runs` times.
+pub fn time<S: Solution>(input: &str, parts: &[u8], runs: usize) -> Result<Samples, ParseError> {
+    let mut parse_times = Vec::with_capacity(runs);
+    // parse once untimed, so a broken input fails before spending any time on it
+    let mut parsed = S::parse(input)?;
+    for _ in 0..runs {
+        let start = Instant::now();
+        parsed = black_box(S::parse(black_box(input))?);
+        parse_times.push(start.elapsed());
+    }
+    let mut samples = vec![(Phase::Parse, parse_times)];
+    for part in parts.iter().filter(|part| **part == 1 || (**part == 2 && S::HAS_PART_2)) {
+        let times = (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                match part {
+                    1 => { black_box(S::part_1(black_box(&parsed))); },
+                    _ => { black_box(S::part_2(black_box(&parsed))); }
+                }
+                start.elapsed()
+            })
+            .collect();
+        samples.push((Phase::Part(*part), times));
+    }
+    Ok(samples)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration
+}
+
+impl Stats {
+    pub fn from(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2 } else { sorted[mid] };
+        Stats { min: sorted[0], median, max: sorted[sorted.len() - 1] }
+    }
+
+    /// Relative change of the median compared to `baseline`, e.g. 0.25 for 25% slower.
+    pub fn change(&self, baseline: &Stats) -> f64 {
+        self.median.as_secs_f64() / baseline.median.as_secs_f64().max(f64::EPSILON) - 1.0
+    }
+
+    pub fn regressed(&self, baseline: &Stats) -> bool {
+        self.change(baseline) > REGRESSION_THRESHOLD && self.median.saturating_sub(baseline.median) > REGRESSION_MIN_DURATION
+    }
+}
+
+/// Stats of every benchmarked day and phase, as saved to and loaded from baseline files.
+pub type Baseline = HashMap<(u8, Phase), Stats>;
+
+/// One line per day and phase: `<day> <phase> <min> <median> <max>`, durations in nanoseconds.
+pub fn save_baseline(path: &str, results: &[(u8, Phase, Stats)]) -> io::Result<()> {
+    let mut contents = String::from("# day phase min_ns median_ns max_ns\n");
+    for (day, phase, stats) in results {
+        contents += &format!("{} {} {} {} {}\n", day, phase.key(), stats.min.as_nanos(), stats.median.as_nanos(), stats.max.as_nanos());
+    }
+    fs::write(path, contents)
+}
+
+pub fn load_baseline(path: &str) -> io::Result<Baseline> {
+    let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid baseline line '{line}'"));
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [day, phase, min, median, max] = fields[..] else {
+                return Err(invalid(line));
+            };
+            let nanos = |field: &str| field.parse::<u64>().map(Duration::from_nanos).map_err(|_| invalid(line));
+            let day = day.parse::<u8>().map_err(|_| invalid(line))?;
+            let phase = Phase::from_key(phase).ok_or_else(|| invalid(line))?;
+            Ok(((day, phase), Stats { min: nanos(min)?, median: nanos(median)?, max: nanos(max)? }))
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub runs: usize,
+    /// File to write the results to as a new baseline.
+    pub save: Option<String>,
+    /// Baseline file to compare the results against.
+    pub compare: Option<String>,
+    /// Also time each of day 15's ways to search the cave.
+    pub solvers: bool
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions { runs: DEFAULT_RUNS, save: None, compare: None, solvers: false }
+    }
+}
+
+/// Benchmarks the given days and prints a report. Returns whether all days could be benchmarked
+/// without any regression compared to the baseline.
+pub fn bench(days: &[&Day], part: Option<u8>, source: &InputSource, options: &BenchOptions) -> bool {
+    let baseline = match &options.compare {
+        Some(path) => match load_baseline(path) {
+            Ok(baseline) => Some(baseline),
+            Err(err) => {
+                eprintln!("error: couldn't read baseline {path}: {err}");
+                return false;
+            }
+        },
+        None => None
+    };
+    let mut ok = true;
+    let mut results = vec![];
+    for day in days {
+        let input = match source.read(day.number) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("error: couldn't read input {} for day {:02}: {}", source.path(day.number), day.number, err);
+                ok = false;
+                continue;
+            }
+        };
+        let samples = match (day.bench)(&input, &day.parts(part), options.runs) {
+            Ok(samples) => samples,
+            Err(err) => {
+                eprintln!("error: couldn't parse input {}: {}", source.path(day.number), err);
+                ok = false;
+                continue;
+            }
+        };
+        for (phase, times) in samples {
+            let stats = Stats::from(&times);
+            let comparison = match baseline.as_ref().map(|baseline| baseline.get(&(day.number, phase))) {
+                Some(Some(base)) => {
+                    let regressed = stats.regressed(base);
+                    ok &= !regressed;
+                    format!("  {:+.1}% vs {:.2?}{}", stats.change(base) * 100.0, base.median, if regressed { "  REGRESSION" } else { "" })
+                },
+                Some(None) => String::from("  no baseline"),
+                None => String::new()
+            };
+            println!("day {:02}, {:<6}  min {:>10.2?}  median {:>10.2?}  max {:>10.2?}{}",
+                day.number, phase.to_string(), stats.min, stats.median, stats.max, comparison);
+            results.push((day.number, phase, stats));
+        }
+    }
+    if let Some(path) = &options.save {
+        match save_baseline(path, &results) {
+            Ok(()) => println!("saved baseline to {path}"),
+            Err(err) => {
+                eprintln!("error: couldn't save baseline {path}: {err}");
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::day_17::Day17;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from(&millis(&[5, 1, 3]));
+        assert_eq!(stats, Stats { min: Duration::from_millis(1), median: Duration::from_millis(3), max: Duration::from_millis(5) });
+        assert_eq!(Stats::from(&millis(&[4, 1, 2, 3])).median, Duration::from_micros(2500));
+        let baseline = Stats::from(&millis(&[2]));
+        assert!((stats.change(&baseline) - 0.5).abs() < 1e-9);
+        assert!(stats.regressed(&baseline));
+        assert!(!baseline.regressed(&stats));
+        let fast = Stats::from(&[Duration::from_nanos(500)]);
+        assert!(!fast.regressed(&Stats::from(&[Duration::from_nanos(100)])));
+    }
+
+    #[test]
+    fn test_time() {
+        let input = fs::read_to_string("./input/day_17.test.txt").unwrap();
+        let samples = time::<Day17>(&input, &[1, 2], 3).unwrap();
+        let phases: Vec<Phase> = samples.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part(1), Phase::Part(2)]);
+        assert!(samples.iter().all(|(_, times)| times.len() == 3));
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let path = env::temp_dir().join(format!("aoc2021_baseline_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let results = vec![
+            (13, Phase::Parse, Stats::from(&millis(&[1, 2, 3]))),
+            (25, Phase::Part(1), Stats::from(&millis(&[40, 50])))
+        ];
+        save_baseline(path, &results).unwrap();
+        let baseline = load_baseline(path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline[&(25, Phase::Part(1))], results[1].2);
+        assert_eq!(Phase::from_key(&Phase::Part(2).key()), Some(Phase::Part(2)));
+    }
+}
@@ -0,0 +1,345 @@
+use std::{fmt::Display, time::Duration};
+
+use aoc2021::{bench::BenchOptions, days::Selection, input::InputSource, log::Level, report::Format, verify};
+
+pub const USAGE: &str = "usage:
+    aoc2021 [-v | -vv | --quiet] <command>
+
+    aoc2021 run <day|all> [--part <1|2>] [--input <file|-> | --input-dir <dir>] [--format <text|json>]
+                [--jobs <n> [--timeout <seconds>]] [--frames <dir>] [--route <file>]
+    aoc2021 bench <day|all> [--part <1|2>] [--input <file|-> | --input-dir <dir>]
+                  [--runs <n>] [--save <file>] [--compare <file>] [--solvers]
+    aoc2021 verify [<day|all>] [--answers <file>]
+    aoc2021 list
+
+options:
+    -v, -vv             report the solvers' progress, or every step they take
+    -q, --quiet         only print answers and errors
+    --input <file|->    read the input of a single day from a file, or from stdin with '-'
+    --input-dir <dir>   read each day's input from <dir>/day_XX.txt (default: ./input)
+    --format <format>   print the answers as 'text' (default) or as one 'json' object per part
+    --jobs <n>          solve every day and part on a pool of <n> threads and print a summary
+    --timeout <seconds> with --jobs, give up on parts still running after <seconds>
+    --frames <dir>      day 13 only: write the paper after each fold to <dir> as text and PBM
+    --route <file>      day 15 only: show the lowest-risk route and write it to <file>, as SVG
+                        if it ends in .svg and as text else; the full cave's with --part 2
+    --runs <n>          how often bench times each phase (default: 10)
+    --save <file>       save the bench results as a baseline
+    --compare <file>    compare the bench results against a saved baseline
+    --solvers           day 15 only: also time each way to search the cave; the full cave's
+                        with --part 2
+    --answers <file>    expected answers to verify (default: ./answers.txt)";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run { selection: Selection, part: Option<u8>, input: InputSource, options: RunOptions },
+    Bench { selection: Selection, part: Option<u8>, input: InputSource, options: BenchOptions },
+    Verify { selection: Selection, answers: String },
+    List
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct RunOptions {
+    pub format: Format,
+    /// Number of threads to solve the days on, sequentially in the main thread if `None`.
+    pub jobs: Option<usize>,
+    /// How long to wait for the parallel jobs.
+    pub timeout: Option<Duration>,
+    /// Directory to write the frames of day 13's folds to.
+    pub frames: Option<String>,
+    /// File to write day 15's lowest-risk route to.
+    pub route: Option<String>
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CliError(String);
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Takes the verbosity flags out of `args`, they can be given anywhere on the command line.
+pub fn split_verbosity<I: IntoIterator<Item = String>>(args: I) -> Result<(Level, Vec<String>), CliError> {
+    let mut verbosity = 0;
+    let mut quiet = false;
+    let mut rest = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            "-q" | "--quiet" => quiet = true,
+            _ => rest.push(arg)
+        }
+    }
+    let level = match (quiet, verbosity) {
+        (true, 0) => Level::Quiet,
+        (true, _) => return Err(CliError(String::from("--quiet can't be combined with -v"))),
+        (false, 0) => Level::Normal,
+        (false, 1) => Level::Verbose,
+        (false, _) => Level::Debug
+    };
+    Ok((level, rest))
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("list") => match args.next() {
+            None => Ok(Command::List),
+            Some(arg) => Err(CliError(format!("unexpected argument '{arg}'")))
+        },
+        Some("run") => parse_run(args, false),
+        Some("bench") => parse_run(args, true),
+        Some("verify") => parse_verify(args),
+        Some(cmd) => Err(CliError(format!("unknown command '{cmd}'"))),
+        None => Err(CliError(String::from("no command given")))
+    }
+}
+
+/// Parses the arguments of `run`, or of `bench` which additionally takes the bench options.
+fn parse_run<I: Iterator<Item = String>>(mut args: I, bench: bool) -> Result<Command, CliError> {
+    let selection = match args.next() {
+        Some(day) => parse_selection(&day)?,
+        None => return Err(CliError(String::from("missing day, expected a number or 'all'")))
+    };
+    let mut part = None;
+    let mut input = None;
+    let mut options = BenchOptions::default();
+    let mut run_options = RunOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => part = Some(parse_part(&expect_value(&arg, args.next())?)?),
+            "--input" | "-i" => {
+                let value = expect_value(&arg, args.next())?;
+                let source = if value == "-" { InputSource::Stdin } else { InputSource::File(value) };
+                input = Some(set_once(input, source)?);
+            },
+            "--input-dir" => input = Some(set_once(input, InputSource::Dir(expect_value(&arg, args.next())?))?),
+            "--format" if !bench => run_options.format = parse_format(&expect_value(&arg, args.next())?)?,
+            "--jobs" if !bench => run_options.jobs = Some(parse_count(&arg, &expect_value(&arg, args.next())?)?),
+            "--frames" if !bench => run_options.frames = Some(expect_value(&arg, args.next())?),
+            "--route" if !bench => run_options.route = Some(expect_value(&arg, args.next())?),
+            "--timeout" if !bench => run_options.timeout = Some(parse_timeout(&expect_value(&arg, args.next())?)?),
+            "--runs" if bench => options.runs = parse_count(&arg, &expect_value(&arg, args.next())?)?,
+            "--save" if bench => options.save = Some(expect_value(&arg, args.next())?),
+            "--compare" if bench => options.compare = Some(expect_value(&arg, args.next())?),
+            "--solvers" if bench => options.solvers = true,
+            _ => return Err(CliError(format!("unexpected argument '{arg}'")))
+        }
+    }
+    let input = input.unwrap_or_default();
+    if selection == Selection::All && !matches!(input, InputSource::Dir(_)) {
+        return Err(CliError(String::from("--input only works for a single day, use --input-dir instead")));
+    }
+    if run_options.timeout.is_some() && run_options.jobs.is_none() {
+        return Err(CliError(String::from("--timeout only works together with --jobs")));
+    }
+    if run_options.frames.is_some() && selection != Selection::Day(13) {
+        return Err(CliError(String::from("--frames only works for day 13")));
+    }
+    if run_options.frames.is_some() && input == InputSource::Stdin {
+        return Err(CliError(String::from("--frames reads the input a second time, it can't come from stdin")));
+    }
+    if run_options.route.is_some() && selection != Selection::Day(15) {
+        return Err(CliError(String::from("--route only works for day 15")));
+    }
+    if run_options.route.is_some() && input == InputSource::Stdin {
+        return Err(CliError(String::from("--route reads the input a second time, it can't come from stdin")));
+    }
+    if options.solvers && selection != Selection::Day(15) {
+        return Err(CliError(String::from("--solvers only works for day 15")));
+    }
+    if options.solvers && input == InputSource::Stdin {
+        return Err(CliError(String::from("--solvers reads the input a second time, it can't come from stdin")));
+    }
+    if bench {
+        return Ok(Command::Bench { selection, part, input, options });
+    }
+    Ok(Command::Run { selection, part, input, options: run_options })
+}
+
+fn parse_verify<I: Iterator<Item = String>>(mut args: I) -> Result<Command, CliError> {
+    let mut selection = Selection::All;
+    let mut answers = String::from(verify::DEFAULT_ANSWERS);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers = expect_value(&arg, args.next())?,
+            _ if arg.starts_with('-') => return Err(CliError(format!("unexpected argument '{arg}'"))),
+            _ => selection = parse_selection(&arg)?
+        }
+    }
+    Ok(Command::Verify { selection, answers })
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, CliError> {
+    value.ok_or_else(|| CliError(format!("missing value for {flag}")))
+}
+
+fn set_once(current: Option<InputSource>, source: InputSource) -> Result<InputSource, CliError> {
+    match current {
+        None => Ok(source),
+        Some(_) => Err(CliError(String::from("only one of --input and --input-dir can be given")))
+    }
+}
+
+fn parse_selection(arg: &str) -> Result<Selection, CliError> {
+    if arg == "all" {
+        return Ok(Selection::All);
+    }
+    arg.trim_start_matches("day_")
+        .parse::<u8>()
+        .map(Selection::Day)
+        .map_err(|_| CliError(format!("invalid day '{arg}', expected a number or 'all'")))
+}
+
+fn parse_part(arg: &str) -> Result<u8, CliError> {
+    match arg {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(CliError(format!("invalid part '{arg}', expected 1 or 2")))
+    }
+}
+
+fn parse_format(arg: &str) -> Result<Format, CliError> {
+    match arg {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(CliError(format!("invalid format '{arg}', expected 'text' or 'json'")))
+    }
+}
+
+/// Parses the positive number given to `--runs` or `--jobs`.
+fn parse_count(flag: &str, arg: &str) -> Result<usize, CliError> {
+    match arg.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(CliError(format!("invalid value '{arg}' for {flag}, expected a positive number")))
+    }
+}
+
+fn parse_timeout(arg: &str) -> Result<Duration, CliError> {
+    match arg.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(CliError(format!("invalid timeout '{arg}', expected a positive number of seconds")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(parse_args(args("run 22 --part 2")),
+            Ok(Command::Run { selection: Selection::Day(22), part: Some(2), input: InputSource::default(), options: RunOptions::default() }));
+        assert_eq!(parse_args(args("run all")),
+            Ok(Command::Run { selection: Selection::All, part: None, input: InputSource::default(), options: RunOptions::default() }));
+        assert_eq!(parse_args(args("run day_13 -p 1")),
+            Ok(Command::Run { selection: Selection::Day(13), part: Some(1), input: InputSource::default(), options: RunOptions::default() }));
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_args(args("run 22 --input mine.txt")),
+            Ok(Command::Run { selection: Selection::Day(22), part: None, input: InputSource::File(String::from("mine.txt")), options: RunOptions::default() }));
+        assert_eq!(parse_args(args("run 22 -i -")),
+            Ok(Command::Run { selection: Selection::Day(22), part: None, input: InputSource::Stdin, options: RunOptions::default() }));
+        assert_eq!(parse_args(args("run all --input-dir fixtures")),
+            Ok(Command::Run { selection: Selection::All, part: None, input: InputSource::Dir(String::from("fixtures")), options: RunOptions::default() }));
+        assert!(parse_args(args("run all --input mine.txt")).is_err());
+        assert_eq!(parse_args(args("run 22 --format json")),
+            Ok(Command::Run { selection: Selection::Day(22), part: None, input: InputSource::default(), options: RunOptions { format: Format::Json, ..RunOptions::default() } }));
+        assert!(parse_args(args("run 22 --format yaml")).is_err());
+        assert!(parse_args(args("bench 22 --format json")).is_err());
+        assert!(parse_args(args("run 22 --input mine.txt --input-dir fixtures")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(parse_args(args("bench all")),
+            Ok(Command::Bench { selection: Selection::All, part: None, input: InputSource::default(), options: BenchOptions::default() }));
+        let options = BenchOptions { runs: 3, save: Some(String::from("new.txt")), compare: Some(String::from("old.txt")), solvers: false };
+        assert_eq!(parse_args(args("bench 14 --runs 3 --part 2 --save new.txt --compare old.txt")),
+            Ok(Command::Bench { selection: Selection::Day(14), part: Some(2), input: InputSource::default(), options }));
+        assert!(parse_args(args("bench 14 --runs 0")).is_err());
+        let options = BenchOptions { solvers: true, ..BenchOptions::default() };
+        assert_eq!(parse_args(args("bench 15 --solvers --part 2")),
+            Ok(Command::Bench { selection: Selection::Day(15), part: Some(2), input: InputSource::default(), options }));
+        assert!(parse_args(args("bench all --solvers")).is_err());
+        assert!(parse_args(args("bench 15 -i - --solvers")).is_err());
+        assert!(parse_args(args("run 15 --solvers")).is_err());
+        assert!(parse_args(args("run 14 --runs 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        let options = RunOptions { jobs: Some(4), timeout: Some(Duration::from_millis(1500)), ..RunOptions::default() };
+        assert_eq!(parse_args(args("run all --jobs 4 --timeout 1.5")),
+            Ok(Command::Run { selection: Selection::All, part: None, input: InputSource::default(), options }));
+        assert!(parse_args(args("run all --jobs 0")).is_err());
+        assert!(parse_args(args("run all --timeout 10")).is_err());
+        assert!(parse_args(args("run all --jobs 2 --timeout -1")).is_err());
+        assert!(parse_args(args("bench all --jobs 2")).is_err());
+    }
+
+    #[test]
+    fn test_parse_frames() {
+        let options = RunOptions { frames: Some(String::from("frames")), ..RunOptions::default() };
+        assert_eq!(parse_args(args("run 13 --frames frames")),
+            Ok(Command::Run { selection: Selection::Day(13), part: None, input: InputSource::default(), options }));
+        assert!(parse_args(args("run 14 --frames frames")).is_err());
+        assert!(parse_args(args("run all --frames frames")).is_err());
+        assert!(parse_args(args("run 13 -i - --frames frames")).is_err());
+        assert!(parse_args(args("run 13 -i input/day_13.txt --frames frames")).is_ok());
+    }
+
+    #[test]
+    fn test_parse_route() {
+        let options = RunOptions { route: Some(String::from("route.svg")), ..RunOptions::default() };
+        assert_eq!(parse_args(args("run 15 --part 2 --route route.svg")),
+            Ok(Command::Run { selection: Selection::Day(15), part: Some(2), input: InputSource::default(), options }));
+        assert!(parse_args(args("run 13 --route route.txt")).is_err());
+        assert!(parse_args(args("bench 15 --route route.txt")).is_err());
+        assert!(parse_args(args("run 15 --input - --route route.txt")).is_err());
+        assert!(parse_args(args("run 15 --input input/day_15.txt --route route.txt")).is_ok());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(parse_args(args("verify")),
+            Ok(Command::Verify { selection: Selection::All, answers: String::from(verify::DEFAULT_ANSWERS) }));
+        assert_eq!(parse_args(args("verify 22 --answers mine.txt")),
+            Ok(Command::Verify { selection: Selection::Day(22), answers: String::from("mine.txt") }));
+        assert!(parse_args(args("verify --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_split_verbosity() {
+        assert_eq!(split_verbosity(args("run 22")), Ok((Level::Normal, args("run 22"))));
+        assert_eq!(split_verbosity(args("-v run 22")), Ok((Level::Verbose, args("run 22"))));
+        assert_eq!(split_verbosity(args("run -vv 22")), Ok((Level::Debug, args("run 22"))));
+        assert_eq!(split_verbosity(args("run 22 -v -v")), Ok((Level::Debug, args("run 22"))));
+        assert_eq!(split_verbosity(args("--quiet bench all")), Ok((Level::Quiet, args("bench all"))));
+        assert!(split_verbosity(args("-q -v run 22")).is_err());
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(parse_args(args("list")), Ok(Command::List));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_args(args("")).is_err());
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run 22 --part 3")).is_err());
+        assert!(parse_args(args("run 22 --part")).is_err());
+        assert!(parse_args(args("run twelve")).is_err());
+        assert!(parse_args(args("jump 22")).is_err());
+    }
+}
@@ -1,99 +1,477 @@
-use std::{fs, collections::HashSet};
+//! Day 13: Transparent Origami. Folds a sheet of transparent paper covered in dots
+//! until the dots spell out a code.
+
+use std::{fmt::Display, fs, io, path::Path};
+
+use crate::{solution::Solution, error::{ParseError, ParseContext}, grid::{Grid, Point}};
+
+/// A dot on the paper at (x, y).
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
-struct Dot(i32, i32);
+pub struct Dot(pub i32, pub i32);
 impl Dot {
-    fn from(str: &str) -> Dot {
-        let from = String::from(str);
-        if let Some(i) = from.find(',') {
-            return Dot(
-                i32::from_str_radix(&from[..i], 10).unwrap(),
-                i32::from_str_radix(&from[i+1..], 10).unwrap()
-            );
+    fn from(str: &str, ctx: &ParseContext) -> Result<Dot, ParseError> {
+        if let Some((x, y)) = str.split_once(',') {
+            return Ok(Dot(
+                ctx.number(x, "an x coordinate")?,
+                ctx.number(y, "a y coordinate")?
+            ));
         }
-        panic!("Separator ',' not found in string {}!", str);
+        Err(ctx.unexpected(str, "a dot like '6,10'"))
     }
 }
-#[derive(Debug)]
-struct Instruction(i32, i32);
-impl Instruction {
-    fn from(entry: Vec<&str>) -> Instruction {
-        if let Ok(num) = i32::from_str_radix(entry[1], 10) {
-           return Instruction((entry[0].chars().nth(11).unwrap() == 'y') as i32, num);
+/// A line to fold the paper along. The part of the paper beyond the line, to the right,
+/// at the bottom, or to the top right or bottom right for the diagonals, is folded onto
+/// the rest of it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Fold {
+    /// `fold along x=7`, the vertical line at x = 7.
+    X(i32),
+    /// `fold along y=7`, the horizontal line at y = 7.
+    Y(i32),
+    /// `fold along x=y through 2,0`, the diagonal going down to the right through a point,
+    /// (0, 0) if none is given.
+    Diagonal(Point),
+    /// `fold along x=-y through 4,4`, the diagonal going up to the right through a point.
+    AntiDiagonal(Point)
+}
+impl Fold {
+    fn from(str: &str, ctx: &ParseContext) -> Result<Fold, ParseError> {
+        let fold = str
+            .strip_prefix("fold along ")
+            .ok_or_else(|| ctx.unexpected(str, "a fold like 'fold along y=7'"))?;
+        let (line, through) = match fold.split_once(" through ") {
+            Some((line, point)) => (line, Some(Dot::from(point, ctx)?)),
+            None => (fold, None)
+        };
+        let (axis, num) = line
+            .split_once('=')
+            .ok_or_else(|| ctx.unexpected(line, "an axis and coordinate like 'y=7' or a diagonal like 'x=-y'"))?;
+        let through = through.map(|Dot(x, y)| (x, y));
+        match (axis, num, through) {
+            ("x", "y", through) => Ok(Fold::Diagonal(through.unwrap_or((0, 0)))),
+            ("x", "-y", through) => Ok(Fold::AntiDiagonal(through.unwrap_or((0, 0)))),
+            ("x", num, None) => Ok(Fold::X(ctx.number(num, "a fold coordinate or 'y'")?)),
+            ("y", num, None) => Ok(Fold::Y(ctx.number(num, "a fold coordinate")?)),
+            ("x" | "y", _, Some(_)) => Err(ctx.unexpected(&fold[line.len()..], "the end of a fold along an axis")),
+            _ => Err(ctx.unexpected(axis, "axis 'x' or 'y'"))
+        }
+    }
+
+    /// How far `point` lies beyond the line, negative if it's on the part that stays.
+    fn side(&self, (x, y): Point) -> i32 {
+        match *self {
+            Fold::X(line) => x - line,
+            Fold::Y(line) => y - line,
+            Fold::Diagonal((px, py)) => (x - y) - (px - py),
+            Fold::AntiDiagonal((px, py)) => (x + y) - (px + py)
+        }
+    }
+
+    /// Where `point` ends up when mirrored at the line.
+    pub fn mirror(&self, (x, y): Point) -> Point {
+        match *self {
+            Fold::X(line) => (line * 2 - x, y),
+            Fold::Y(line) => (x, line * 2 - y),
+            Fold::Diagonal((px, py)) => (y + px - py, x - px + py),
+            Fold::AntiDiagonal((px, py)) => (px + py - y, px + py - x)
+        }
+    }
+}
+impl Display for Fold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fold::X(line) => write!(f, "fold along x={line}"),
+            Fold::Y(line) => write!(f, "fold along y={line}"),
+            Fold::Diagonal((0, 0)) => write!(f, "fold along x=y"),
+            Fold::Diagonal((x, y)) => write!(f, "fold along x=y through {x},{y}"),
+            Fold::AntiDiagonal((0, 0)) => write!(f, "fold along x=-y"),
+            Fold::AntiDiagonal((x, y)) => write!(f, "fold along x=-y through {x},{y}")
         }
-        panic!("Invalid entries for instruction {:?}!", entry);
     }
 }
 
-pub fn part_1() {
-    let (instructions, dots) = read_input();
-    fold_first(instructions, dots);
+/// A fold that can't be done on the paper.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum FoldError {
+    /// A dot lies right on the fold line.
+    DotOnLine { fold: Fold, dot: Point },
+    /// A dot would end up beyond the top or left edge of the paper.
+    PastEdge { fold: Fold, dot: Point, folded: Point }
+}
+impl FoldError {
+    /// What a legal fold looks like, for reporting it as a parse error.
+    fn expected(&self) -> String {
+        match self {
+            FoldError::DotOnLine { dot, .. } => format!("a fold line without dots, but {},{} lies on it", dot.0, dot.1),
+            FoldError::PastEdge { dot, folded, .. } =>
+                format!("a fold within the paper, but it moves {},{} past its edge to {},{}", dot.0, dot.1, folded.0, folded.1)
+        }
+    }
+}
+impl Display for FoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FoldError::DotOnLine { fold, dot } => write!(f, "{fold}: dot {},{} lies on the fold line", dot.0, dot.1),
+            FoldError::PastEdge { fold, dot, folded } =>
+                write!(f, "{fold}: dot {},{} would be folded past the edge of the paper to {},{}", dot.0, dot.1, folded.0, folded.1)
+        }
+    }
 }
+impl std::error::Error for FoldError {}
 
-pub fn part_2() {
-    let (instructions, dots) = read_input();
-    fold(instructions, dots);
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Input = (Vec<Fold>, Vec<Dot>);
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_input(input)
+    }
+    // the folds were checked while parsing
+    fn part_1((folds, dots): &Self::Input) -> usize {
+        fold_first(folds, dots).unwrap()
+    }
+    fn part_2((folds, dots): &Self::Input) -> String {
+        let paper = fold(folds, dots).unwrap();
+        read_letters(&paper).unwrap_or_else(|| format!("\n{}", render(&paper).trim_end()))
+    }
 }
 
-fn read_input() -> (Vec<Instruction>, Vec<Dot>) {
-    let contents =
-        fs::read_to_string("./input/day_13.txt")
-        .expect("Something went wrong reading the file");
-    let splits = contents.split("\n\n").collect::<Vec<&str>>();
-    let instructions = splits[1]
-        .trim()
-        .split("\n")
-        .map(|instr| Instruction::from(instr.split("=").collect::<Vec<&str>>()))
-        .collect();
-    let result_dots = splits[0]
+/// Reads the dots and the folds following them after an empty line. Folds that can't
+/// be done on the paper are reported as errors, too.
+pub fn read_input(contents: &str) -> Result<(Vec<Fold>, Vec<Dot>), ParseError> {
+    let ctx = ParseContext::new(Day13::DAY, contents);
+    let (dots, instructions) = contents
+        .split_once("\n\n")
+        .ok_or_else(|| ctx.missing_after(contents.trim_end(), "an empty line followed by folds"))?;
+    let lines: Vec<&str> = instructions.trim().lines().collect();
+    let folds = lines
+        .iter()
+        .map(|instr| Fold::from(instr, &ctx))
+        .collect::<Result<Vec<_>, _>>()?;
+    let result_dots: Vec<Dot> = dots
         .trim()
-        .split("\n")
-        .map(|dot| Dot::from(dot))
-        .collect();
-    (instructions, result_dots)
+        .lines()
+        .map(|dot| Dot::from(dot, &ctx))
+        .collect::<Result<_, _>>()?;
+    if let Some((i, Err(err))) = fold_steps(&folds, &result_dots).enumerate().find(|(_, step)| step.is_err()) {
+        return Err(ctx.unexpected(lines[i], &err.expected()));
+    }
+    Ok((folds, result_dots))
 }
 
-fn fold_first(instructions: Vec<Instruction>, dots: Vec<Dot>) {
-    let axis = instructions[0].0;
-    let coord = instructions[0].1;
-    let result_dots = dots
-        .iter()
-        .fold(HashSet::<Dot>::from_iter(dots.iter().cloned()), |mut acc, cur| {
-            if axis == 0 && cur.0 >= coord {
-                acc.remove(&cur);
-                acc.insert(Dot(coord * 2 - cur.0, cur.1));
-            } else if axis == 1 && cur.1 >= coord {
-                acc.remove(&cur);
-                acc.insert(Dot(cur.0, coord * 2 - cur.1));
-            }
-            acc
-        });
-    println!("Result number dots: {}", result_dots.len())
+/// The paper with a cell set for each dot on it.
+pub fn paper(dots: &[Dot]) -> Grid<bool> {
+    Grid::from_points(dots.iter().map(|dot| (dot.0, dot.1)), true, false)
 }
 
-fn fold(instructions: Vec<Instruction>, dots: Vec<Dot>) {
-    let result_hash_set = instructions
-        .iter()
-        .fold(HashSet::<Dot>::from_iter(dots.iter().cloned()), |acc, Instruction(axis, coord)| acc
-            .iter()
-            .fold(acc.clone(), |mut acc, cur| {
-                if *axis == 0 && cur.0 >= *coord {
-                    acc.remove(&cur);
-                    acc.insert(Dot(coord * 2 - cur.0, cur.1));
-                } else if *axis == 1 && cur.1 >= *coord {
-                    acc.remove(&cur);
-                    acc.insert(Dot(cur.0, coord * 2 - cur.1));
-                }
-                acc
-            })
-    );
-    for y in 0..10 {
-        println!("");
-        for x in 0..50 {
-            if result_hash_set.contains(&Dot(x as i32, y as i32)) {
-                print!("#");
-            } else {
-                print!(".");
+/// Folds the paper once, the dots beyond the fold line are mirrored onto the rest of it.
+/// Fails if a dot lies on the line or would end up at a negative coordinate.
+pub fn fold_paper(paper: &Grid<bool>, fold: &Fold) -> Result<Grid<bool>, FoldError> {
+    let (min, max) = (paper.min(), paper.max());
+    let mut folded = match fold {
+        Fold::X(line) => Grid::with_bounds(min, (line - 1, max.1), false),
+        Fold::Y(line) => Grid::with_bounds(min, (max.0, line - 1), false),
+        // the diagonals don't make the paper any smaller
+        _ => Grid::with_bounds(min, max, false)
+    };
+    for (dot, _) in paper.iter().filter(|(_, dot)| **dot) {
+        let side = fold.side(dot);
+        if side == 0 {
+            return Err(FoldError::DotOnLine { fold: *fold, dot });
+        }
+        let target = if side > 0 { fold.mirror(dot) } else { dot };
+        if target.0 < 0 || target.1 < 0 {
+            return Err(FoldError::PastEdge { fold: *fold, dot, folded: target });
+        }
+        folded.set(target, true);
+    }
+    Ok(folded)
+}
+
+pub fn count_dots(paper: &Grid<bool>) -> usize {
+    paper.iter().filter(|(_, dot)| **dot).count()
+}
+
+/// The paper after a fold.
+#[derive(Debug, Clone)]
+pub struct FoldStep {
+    /// Number of the fold, starting at 1.
+    pub number: usize,
+    pub fold: Fold,
+    pub paper: Grid<bool>,
+    /// Number of dots left on the paper.
+    pub dots: usize,
+    /// Number of dots that landed on another dot and merged with it.
+    pub merged: usize,
+    pub width: usize,
+    pub height: usize
+}
+
+/// Iterator over the folds, see [`fold_steps`].
+pub struct FoldSteps<'a> {
+    folds: std::slice::Iter<'a, Fold>,
+    paper: Grid<bool>,
+    number: usize
+}
+
+impl Iterator for FoldSteps<'_> {
+    type Item = Result<FoldStep, FoldError>;
+
+    fn next(&mut self) -> Option<Result<FoldStep, FoldError>> {
+        let fold = *self.folds.next()?;
+        let folded = match fold_paper(&self.paper, &fold) {
+            Ok(folded) => folded,
+            Err(err) => {
+                // the paper can't be folded any further
+                self.folds = [].iter();
+                return Some(Err(err));
             }
+        };
+        let dots = count_dots(&folded);
+        let merged = count_dots(&self.paper) - dots;
+        self.paper = folded;
+        self.number += 1;
+        Some(Ok(FoldStep {
+            number: self.number,
+            fold,
+            paper: self.paper.clone(),
+            dots,
+            merged,
+            width: self.paper.width(),
+            height: self.paper.height()
+        }))
+    }
+}
+
+/// Applies the folds one at a time, yielding the paper after each of them, or the error
+/// of the first fold that couldn't be done.
+pub fn fold_steps<'a>(folds: &'a [Fold], dots: &[Dot]) -> FoldSteps<'a> {
+    FoldSteps { folds: folds.iter(), paper: paper(dots), number: 0 }
+}
+
+/// Number of dots visible after only the first fold.
+pub fn fold_first(folds: &[Fold], dots: &[Dot]) -> Result<usize, FoldError> {
+    fold_steps(folds, dots).next().map_or(Ok(dots.len()), |step| step.map(|step| step.dots))
+}
+
+/// Applies all folds, overlapping dots merge into one.
+pub fn fold(folds: &[Fold], dots: &[Dot]) -> Result<Grid<bool>, FoldError> {
+    fold_steps(folds, dots).try_fold(paper(dots), |_, step| step.map(|step| step.paper))
+}
+
+/// Writes the paper before and after each fold to `dir` as numbered frames, both as text
+/// and as PBM image: `fold_00.txt` and `fold_00.pbm` before the first fold, `fold_01.*`
+/// after it and so on. Returns the fold steps.
+pub fn write_frames(folds: &[Fold], dots: &[Dot], dir: &Path) -> io::Result<Vec<FoldStep>> {
+    fs::create_dir_all(dir)?;
+    let write_frame = |number: usize, paper: &Grid<bool>| -> io::Result<()> {
+        fs::write(dir.join(format!("fold_{number:02}.txt")), render(paper))?;
+        fs::write(dir.join(format!("fold_{number:02}.pbm")), to_pbm(paper))
+    };
+    write_frame(0, &paper(dots))?;
+    fold_steps(folds, dots)
+        .map(|step| {
+            let step = step.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            write_frame(step.number, &step.paper).map(|_| step)
+        })
+        .collect()
+}
+
+/// Top left and bottom right corner of the smallest rectangle holding all dots.
+pub fn dot_bounds(paper: &Grid<bool>) -> Option<(Point, Point)> {
+    paper.bounding_box(|dot| *dot)
+}
+
+/// Draws the dots of the paper, before or after any fold, with a `#` for each dot and
+/// a `.` for each empty spot within their bounding box, one line per row.
+pub fn render(paper: &Grid<bool>) -> String {
+    match dot_bounds(paper) {
+        Some((min, max)) => paper.render_area(min, max, |dot| if *dot { '#' } else { '.' }),
+        None => String::new()
+    }
+}
+
+/// The dots within their bounding box as a plain PBM image, which most image viewers
+/// can open: a `P1` header with the size, then a row of `1` for dots and `0` otherwise
+/// per line.
+pub fn to_pbm(paper: &Grid<bool>) -> String {
+    let (min, max) = dot_bounds(paper).unwrap_or(((0, 0), (-1, -1)));
+    let mut pbm = format!("P1\n{} {}\n", max.0 - min.0 + 1, max.1 - min.1 + 1);
+    for y in min.1..=max.1 {
+        let row: Vec<&str> = (min.0..=max.0).map(|x| if *paper.at((x, y)) { "1" } else { "0" }).collect();
+        pbm += &row.join(" ");
+        pbm.push('\n');
+    }
+    pbm
+}
+
+/// The letters of the 4x6 font used for codes, each as its rows from top to bottom.
+const FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"])
+];
+
+/// Reads the code spelled by the dots, with letters 4 dots wide and 6 high, separated by
+/// an empty column, wherever their bounding box is on the paper. `None` if any of them
+/// isn't a known letter, or if there are dots that aren't part of a letter.
+pub fn read_letters(paper: &Grid<bool>) -> Option<String> {
+    let (min, max) = dot_bounds(paper)?;
+    if max.1 - min.1 != 5 {
+        return None;
+    }
+    // the box is a column narrower if the code starts with an I, whose first column is empty
+    let letters = (max.0 - min.0 + 3) / 5;
+    let left = max.0 - 5 * letters + 2;
+    if left < min.0 - 1 || left > min.0 {
+        return None;
+    }
+    let gaps_empty = (1..letters).all(|i| (min.1..=max.1).all(|y| !*paper.at((left + i * 5 - 1, y))));
+    if !gaps_empty {
+        return None;
+    }
+    (0..letters)
+        .map(|i| {
+            let glyph: Vec<String> = (0..6)
+                .map(|y| (0..4).map(|x| if *paper.at((left + i * 5 + x, min.1 + y)) { '#' } else { '.' }).collect())
+                .collect();
+            FONT.iter().find(|(_, rows)| *rows == glyph[..]).map(|(letter, _)| *letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::{day_13::*, solution::Solution};
+    #[test]
+    fn part_1() {
+        let input = Day13::parse(&fs::read_to_string("./input/day_13.test.txt").unwrap()).unwrap();
+        assert_eq!(Day13::part_1(&input), 17);
+    }
+    #[test]
+    fn test_fold_steps() {
+        let (instructions, dots) = Day13::parse(&fs::read_to_string("./input/day_13.test.txt").unwrap()).unwrap();
+        let steps: Vec<FoldStep> = fold_steps(&instructions, &dots).collect::<Result<_, _>>().unwrap();
+        let stats: Vec<_> = steps.iter().map(|step| (step.number, step.fold.to_string(), step.dots, step.merged, step.width, step.height)).collect();
+        assert_eq!(stats, vec![
+            (1, String::from("fold along y=7"), 17, 1, 11, 7),
+            (2, String::from("fold along x=5"), 16, 1, 5, 7)
+        ]);
+        assert_eq!(steps[1].paper, fold(&instructions, &dots).unwrap());
+
+        let dir = std::env::temp_dir().join(format!("aoc2021_frames_{}", std::process::id()));
+        assert_eq!(write_frames(&instructions, &dots, &dir).unwrap().len(), 2);
+        let frame = fs::read_to_string(dir.join("fold_02.txt"));
+        let files = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(frame.unwrap(), render(&steps[1].paper));
+        assert_eq!(files, 6);
+    }
+    #[test]
+    fn test_render() {
+        let (instructions, dots) = Day13::parse(&fs::read_to_string("./input/day_13.test.txt").unwrap()).unwrap();
+        let paper = fold_paper(&paper(&dots), &instructions[0]).unwrap();
+        assert_eq!(render(&paper), "\
+#.##..#..#.
+#...#......
+......#...#
+#...#......
+.#.#..#.###
+");
+        let folded = fold(&instructions, &dots).unwrap();
+        assert_eq!(dot_bounds(&folded), Some(((0, 0), (4, 4))));
+        assert_eq!(render(&folded), "#####\n#...#\n#...#\n#...#\n#####\n");
+        assert_eq!(to_pbm(&folded), "P1\n5 5\n1 1 1 1 1\n1 0 0 0 1\n1 0 0 0 1\n1 0 0 0 1\n1 1 1 1 1\n");
+        let offset = Grid::from_points([(-2, 3), (1, 4)], true, false);
+        assert_eq!(render(&offset), "#...\n...#\n");
+        assert_eq!(to_pbm(&Grid::new(2, 2, false)), "P1\n0 0\n");
+    }
+    #[test]
+    fn test_read_letters() {
+        let image = "\
+###..###...##..#..#.####
+#..#.#..#.#..#.#.#..#...
+#..#.#..#.#....##...###.
+###..###..#....#.#..#...
+#.#..#....#..#.#.#..#...
+#..#.#.....##..#..#.#...
+";
+        let paper = Grid::parse(image, &ParseContext::new(Day13::DAY, image), "'#' or '.'", |c| Some(c == '#')).unwrap();
+        assert_eq!(read_letters(&paper), Some(String::from("RPCKF")));
+        // away from the top left corner, starting with the empty first column of an I
+        let image = "\
+............
+...###...##.
+....#.....#.
+....#.....#.
+....#.....#.
+....#..#..#.
+...###..##..
+............
+";
+        let parse = |image: &str| Grid::parse(image, &ParseContext::new(Day13::DAY, image), "'#' or '.'", |c| Some(c == '#')).unwrap();
+        assert_eq!(read_letters(&parse(image)), Some(String::from("IJ")));
+        // a stray dot after the last letter, in the gap between the letters, or below them
+        let with_dot = |(x, y): (usize, usize)| {
+            let mut rows: Vec<String> = image.lines().map(String::from).collect();
+            rows[y].replace_range(x..=x, "#");
+            parse(&rows.join("\n"))
+        };
+        for stray in [(11, 1), (7, 3), (4, 7)] {
+            assert_eq!(read_letters(&with_dot(stray)), None, "{stray:?}");
         }
+        let input = Day13::parse(&fs::read_to_string("./input/day_13.test.txt").unwrap()).unwrap();
+        assert_eq!(read_letters(&fold(&input.0, &input.1).unwrap()), None);
+    }
+    #[test]
+    fn test_diagonal_folds() {
+        let (folds, dots) = read_input("3,0\n0,2\n4,1\n\nfold along x=y\nfold along x=-y through 2,2\n").unwrap();
+        assert_eq!(folds, vec![Fold::Diagonal((0, 0)), Fold::AntiDiagonal((2, 2))]);
+        assert_eq!(folds[1].to_string(), "fold along x=-y through 2,2");
+        let steps: Vec<FoldStep> = fold_steps(&folds, &dots).collect::<Result<_, _>>().unwrap();
+        assert_eq!(render(&steps[0].paper), "#.\n#.\n.#\n");
+        assert_eq!(steps[0].dots, 3);
+        assert_eq!(Fold::Diagonal((2, 0)).mirror((5, 1)), (3, 3));
+        assert_eq!(Fold::AntiDiagonal((4, 4)).mirror((9, 0)), (8, -1));
+    }
+    #[test]
+    fn test_fold_errors() {
+        let dots = [Dot(3, 7), Dot(1, 2)];
+        assert_eq!(fold_first(&[Fold::Y(7)], &dots), Err(FoldError::DotOnLine { fold: Fold::Y(7), dot: (3, 7) }));
+        assert_eq!(fold_first(&[Fold::X(2)], &dots), Ok(2));
+        assert_eq!(fold_first(&[Fold::X(0)], &[Dot(3, 7)]), Err(FoldError::PastEdge { fold: Fold::X(0), dot: (3, 7), folded: (-3, 7) }));
+        let err = fold(&[Fold::Y(3)], &dots).unwrap_err();
+        assert_eq!(err.to_string(), "fold along y=3: dot 3,7 would be folded past the edge of the paper to 3,-1");
+        let err = read_input("6,10\n0,14\n\nfold along y=7\nfold along x=6\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 13, line 5, column 1: expected a fold line without dots, but 6,4 lies on it, found \"fold along x=6\"");
+        assert_eq!(read_input("6,10\n\nfold along y=7 through 1,1\n").unwrap_err().position(), (3, 15));
+    }
+    #[test]
+    fn test_parse_errors() {
+        let err = read_input("6,10\n0,x4\n\nfold along y=7\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 13, line 2, column 3: expected a y coordinate, found \"x4\"");
+        let err = read_input("6,10\n\nfold along z=7\n").unwrap_err();
+        assert_eq!(err.position(), (3, 12));
+        assert!(matches!(read_input("6,10\n0,14\n"), Err(ParseError::UnexpectedEnd { line: 2, .. })));
     }
 }
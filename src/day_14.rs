@@ -1,33 +1,82 @@
 
-use std::fs;
+//! Day 14: Extended Polymerization. Grows a polymer by inserting an element between
+//! every pair of elements, according to a set of pair insertion rules.
+
 use std::collections::{LinkedList, HashMap};
 
-fn read_input(path: &str) -> (LinkedList<char>, HashMap<char, HashMap<char, char>>) {
-    let input = fs::read_to_string(path).unwrap();
-    let split_input: Vec<&str> = input.trim().split("\n\n").collect();
+use crate::{solution::Solution, error::{ParseError, ParseContext}};
+pub use self::{analysis::*, big_uint::*, expansion::*, matrix::*};
+mod analysis;
+mod big_uint;
+mod expansion;
+mod matrix;
+
+/// Element to insert between two elements, looked up by the first and then the second one.
+pub type Rules = HashMap<char, HashMap<char, char>>;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    type Input = (LinkedList<char>, Rules);
+    type Part1 = i32;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_input(input)
+    }
+    fn part_1((template, instructions): &Self::Input) -> i32 {
+        polymer_linked_list(10, template, instructions)
+    }
+    fn part_2((template, instructions): &Self::Input) -> u64 {
+        polymer_fast(40, template, instructions)
+    }
+}
+
+/// Reads the polymer template and the pair insertion rules.
+pub fn read_input(input: &str) -> Result<(LinkedList<char>, Rules), ParseError> {
+    let ctx = ParseContext::new(Day14::DAY, input);
+    let (template, rules) = input
+        .trim()
+        .split_once("\n\n")
+        .ok_or_else(|| ctx.missing_after(input.trim_end(), "an empty line followed by insertion rules"))?;
+    if template.is_empty() || !template.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(ctx.unexpected(template, "a polymer template of uppercase elements"));
+    }
     let template = LinkedList::from_iter(
-        split_input[0].chars());
-    let instructions: HashMap<char, HashMap<char, char>> = split_input[1]
+        template.chars());
+    let instructions: Rules = rules
         .trim()
-        .split("\n")
-        .map(|str| str
-            .split(" -> ")
-            .map(|str| str.chars())
-            .flatten()
-            .collect::<Vec<char>>())
-        .fold(HashMap::new(), | mut acc, chars | {
+        .lines()
+        .map(|str| {
+            let (pair, insert) = str
+                .split_once(" -> ")
+                .ok_or_else(|| ctx.unexpected(str, "a rule like 'CH -> B'"))?;
+            if pair.chars().count() != 2 {
+                return Err(ctx.unexpected(pair, "a pair of two elements"));
+            }
+            if insert.chars().count() != 1 {
+                return Err(ctx.unexpected(insert, "a single element to insert"));
+            }
+            Ok(pair.chars().chain(insert.chars()).collect::<Vec<char>>())
+        })
+        .try_fold(HashMap::new(), | mut acc: Rules, chars: Result<Vec<char>, ParseError> | {
+            let chars = chars?;
             let entry = acc.entry(chars[0]);
             entry
-                .or_insert(HashMap::new())
+                .or_default()
                 .entry(chars[1]).or_insert(chars[2]);
             
-            return acc;
-        });
-    (template, instructions)
+            Ok(acc)
+        })?;
+    Ok((template, instructions))
 }
 
-fn polymer_linked_list(iterations: i32, path: &str) -> i32 {
-    let (mut template, instructions) = read_input(path);
+/// Builds the whole polymer for a number of steps, then returns the count of the most
+/// minus the count of the least common element. Only feasible for few steps. Nothing gets
+/// inserted between a pair without a rule, see `analyze_rules` for finding those.
+pub fn polymer_linked_list(iterations: i32, template: &LinkedList<char>, instructions: &Rules) -> i32 {
+    let mut template = template.clone();
     let mut new_list = LinkedList::new();
     let mut char_counts = HashMap::new();
     for _ in 0..iterations {
@@ -37,9 +86,10 @@ fn polymer_linked_list(iterations: i32, path: &str) -> i32 {
         new_list.push_back(*cur);
         char_counts.entry(*cur).and_modify(|val| *val += 1).or_insert(1);
         while let (Some(cur), Some(next)) = (cursor.current(), cursor.peek_next()) {
-            let insert_char = instructions.get(cur).unwrap().get(next).unwrap();
-            new_list.push_back(*insert_char);
-            char_counts.entry(*insert_char).and_modify(|val| *val += 1).or_insert(1);
+            if let Some(insert_char) = instructions.get(cur).and_then(|inserts| inserts.get(next)) {
+                new_list.push_back(*insert_char);
+                char_counts.entry(*insert_char).and_modify(|val| *val += 1).or_insert(1);
+            }
             new_list.push_back(*next);
             char_counts.entry(*next).and_modify(|val| *val += 1).or_insert(1);
             cursor.move_next();
@@ -51,57 +101,40 @@ fn polymer_linked_list(iterations: i32, path: &str) -> i32 {
         - char_counts.iter().min_by(|(_, a), (_, b)| a.cmp(b)).unwrap().1
 }
 
-fn polymer_fast(iterations: i32, path: &str) -> u64 {
-    let (template, instructions) = read_input(path);
-    let mut patterns = HashMap::new();
-    let mut cursor = template.cursor_front();
-    let mut char_counts: HashMap<char, u64> = HashMap::new();
-    while let (Some(cur), Some(next)) = (cursor.current(), cursor.peek_next()) {
-        patterns.entry((cur, next)).and_modify(|f| *f += 1u64).or_insert(1);
-        char_counts.entry(*cur).and_modify(|count| *count += 1).or_insert(1);
-        cursor.move_next();
-    }
-    char_counts.entry(*template.back().unwrap()).and_modify(|count| *count += 1).or_insert(1);
-
-    let mut cur_patterns = patterns;
-    for _ in 0..iterations {
-        let mut new_patterns = HashMap::new();
-        for ((from, to), count) in cur_patterns {
-            let new_char = instructions.get(from).unwrap().get(to).unwrap();
-            new_patterns.entry((from, new_char))
-                .and_modify(|f| *f += count)
-                .or_insert(count);
-            new_patterns.entry((new_char, to))
-                .and_modify(|f| *f += count)
-                .or_insert(count);
-            char_counts
-                .entry(*new_char)
-                .and_modify(|c| *c += count)
-                .or_insert(count);
-        }
-        cur_patterns = new_patterns;
-    };
-    char_counts.iter().max_by(|(_, x), (_, y)| x.cmp(y)).unwrap().1
-        - char_counts.iter().min_by(|(_, x), (_, y)| x.cmp(y)).unwrap().1
-}
-
-pub fn part_1() {
-    polymer_linked_list(10, "./input/day_14.txt");
-}
-
-pub fn part_2() {
-    polymer_fast(40, "./input/day_14.txt");
+/// Same as `polymer_linked_list`, but only counts pairs instead of building the polymer.
+/// `Polymerization` has the full counts of every step.
+pub fn polymer_fast(iterations: i32, template: &LinkedList<char>, instructions: &Rules) -> u64 {
+    Polymerization::new(template, instructions)
+        .nth(iterations as usize)
+        .unwrap()
+        .spread()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_14::*;
     #[test]
     fn part_1() {
-        assert_eq!(polymer_linked_list(10, "./input/day_14.test.txt"), 1588);
+        let (template, instructions) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        assert_eq!(polymer_linked_list(10, &template, &instructions), 1588);
     }
     #[test]
     fn part_2() {
-        assert_eq!(polymer_fast(40, "./input/day_14.test.txt"), 2188189693529);
+        let (template, instructions) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        assert_eq!(polymer_fast(40, &template, &instructions), 2188189693529);
+    }
+    #[test]
+    fn test_missing_rules() {
+        let (template, instructions) = read_input("NNCB\n\nNN -> C\nNC -> B\n").unwrap();
+        assert_eq!(polymer_linked_list(2, &template, &instructions), 1);
+        assert_eq!(polymer_fast(2, &template, &instructions), 1);
+    }
+    #[test]
+    fn test_parse_errors() {
+        let err = read_input("NNCB\n\nCH -> B\nHH - N\n").unwrap_err();
+        assert_eq!(err.position(), (4, 1));
+        let err = read_input("NNCB\n\nCH -> B\nHHH -> N\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 14, line 4, column 1: expected a pair of two elements, found \"HHH\"");
     }
 }
\ No newline at end of file
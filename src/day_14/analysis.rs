@@ -0,0 +1,199 @@
+use std::collections::{BTreeSet, HashMap, HashSet, LinkedList};
+
+use super::{PairSpace, Rules};
+
+/// Counts of a polymer after some number of steps.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Histogram {
+    pub step: usize,
+    /// How often each element occurs in the polymer.
+    pub elements: HashMap<char, u64>,
+    /// How often each pair of neighbouring elements occurs in the polymer.
+    pub pairs: HashMap<(char, char), u64>
+}
+
+impl Histogram {
+    fn new(template: &LinkedList<char>) -> Histogram {
+        let mut elements = HashMap::new();
+        for element in template {
+            *elements.entry(*element).or_insert(0) += 1;
+        }
+        let mut pairs = HashMap::new();
+        for (a, b) in template.iter().zip(template.iter().skip(1)) {
+            *pairs.entry((*a, *b)).or_insert(0) += 1;
+        }
+        Histogram { step: 0, elements, pairs }
+    }
+
+    /// Count of the most minus the count of the least common element.
+    pub fn spread(&self) -> u64 {
+        self.elements.values().max().unwrap_or(&0) - self.elements.values().min().unwrap_or(&0)
+    }
+}
+
+/// The histograms after step 0, 1, 2, … of growing a polymer, counting pairs like
+/// `polymer_fast`. A pair without a rule stays as it is. The counts roughly double with
+/// every step and overflow a `u64` after about 60 steps; `element_counts_exact` goes on.
+pub struct Polymerization<'a> {
+    rules: &'a Rules,
+    next: Histogram
+}
+
+impl<'a> Polymerization<'a> {
+    pub fn new(template: &LinkedList<char>, rules: &'a Rules) -> Polymerization<'a> {
+        Polymerization { rules, next: Histogram::new(template) }
+    }
+}
+
+impl Iterator for Polymerization<'_> {
+    type Item = Histogram;
+
+    fn next(&mut self) -> Option<Histogram> {
+        let mut elements = self.next.elements.clone();
+        let mut pairs = HashMap::new();
+        for (&(a, b), &count) in &self.next.pairs {
+            match self.rules.get(&a).and_then(|inserts| inserts.get(&b)) {
+                Some(&insert) => {
+                    *pairs.entry((a, insert)).or_insert(0) += count;
+                    *pairs.entry((insert, b)).or_insert(0) += count;
+                    *elements.entry(insert).or_insert(0) += count;
+                }
+                None => *pairs.entry((a, b)).or_insert(0) += count
+            }
+        }
+        let next = Histogram { step: self.next.step + 1, elements, pairs };
+        Some(std::mem::replace(&mut self.next, next))
+    }
+}
+
+/// What a set of rules does to a template.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RuleReport {
+    /// Pairs that show up in the polymer, but have no rule, sorted.
+    pub missing_rules: Vec<(char, char)>,
+    /// Elements of the template or the rules that never show up in the polymer, sorted.
+    pub unproducible: Vec<char>,
+    /// The step from which on the share of each element changes by less than the
+    /// tolerance per step, `None` if it doesn't settle within the steps looked at.
+    pub converged_at: Option<usize>,
+    /// The share of each element of the polymer at the last step looked at.
+    pub ratios: HashMap<char, f64>
+}
+
+/// Checks which pairs of the polymer lack a rule, which elements can never be produced,
+/// and whether the share of each element settles within `max_steps` steps. Shares are
+/// computed from pair frequencies, which unlike counts don't overflow.
+pub fn analyze_rules(template: &LinkedList<char>, rules: &Rules, max_steps: usize, tolerance: f64) -> RuleReport {
+    let space = PairSpace::new(template, rules);
+    let mut reachable: HashSet<usize> = template.iter().zip(template.iter().skip(1)).map(|(a, b)| space.index((*a, *b))).collect();
+    let mut queue: Vec<usize> = reachable.iter().copied().collect();
+    while let Some(index) = queue.pop() {
+        for successor in space.successors(index, rules) {
+            if reachable.insert(successor) {
+                queue.push(successor);
+            }
+        }
+    }
+    let missing_rules: BTreeSet<(char, char)> = reachable
+        .iter()
+        .map(|index| space.pair(*index))
+        .filter(|(a, b)| rules.get(a).and_then(|inserts| inserts.get(b)).is_none())
+        .collect();
+    let produced: HashSet<char> = template.iter().copied().chain(reachable.iter().map(|index| space.pair(*index).1)).collect();
+    let unproducible = space.elements().iter().copied().filter(|element| !produced.contains(element)).collect();
+
+    let last = *template.back().expect("a non-empty template");
+    if template.len() == 1 {
+        // without pairs nothing ever gets inserted, and there's nothing to scale by
+        let ratios = space.elements().iter().map(|element| (*element, if *element == last { 1.0 } else { 0.0 })).collect();
+        return RuleReport { missing_rules: missing_rules.into_iter().collect(), unproducible, converged_at: Some(0), ratios };
+    }
+    let mut frequencies: Vec<f64> = space.initial_counts(template).into_iter().map(|count| count as f64).collect();
+    // the last element is counted on its own, with the same scale as the pairs
+    let mut last_weight = 1.0;
+    let mut ratios = shares(&space, &frequencies, last, last_weight);
+    let mut converged_at = None;
+    for step in 1..=max_steps {
+        let mut next = vec![0.0; space.pair_count()];
+        for (index, frequency) in frequencies.iter().enumerate().filter(|(_, frequency)| **frequency > 0.0) {
+            for successor in space.successors(index, rules) {
+                next[successor] += frequency;
+            }
+        }
+        // scale everything down so the pairs add up to 1 and the numbers don't blow up
+        let total: f64 = next.iter().sum();
+        frequencies = next.into_iter().map(|frequency| frequency / total).collect();
+        last_weight /= total;
+        let next_ratios = shares(&space, &frequencies, last, last_weight);
+        let change = next_ratios.iter().map(|(element, share)| (share - ratios[element]).abs()).fold(0.0, f64::max);
+        ratios = next_ratios;
+        if change >= tolerance {
+            converged_at = None;
+        } else if converged_at.is_none() {
+            converged_at = Some(step - 1);
+        }
+    }
+    RuleReport { missing_rules: missing_rules.into_iter().collect(), unproducible, converged_at, ratios }
+}
+
+/// The share of each element, every element being the first of a pair except the last.
+fn shares(space: &PairSpace, frequencies: &[f64], last: char, last_weight: f64) -> HashMap<char, f64> {
+    let mut shares: HashMap<char, f64> = space.elements().iter().map(|element| (*element, 0.0)).collect();
+    for (index, frequency) in frequencies.iter().enumerate() {
+        *shares.get_mut(&space.pair(index).0).unwrap() += frequency;
+    }
+    *shares.get_mut(&last).unwrap() += last_weight;
+    let total: f64 = shares.values().sum();
+    shares.values_mut().for_each(|share| *share /= total);
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::day_14::read_input;
+
+    #[test]
+    fn test_histograms() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        let histograms: Vec<Histogram> = Polymerization::new(&template, &rules).take(11).collect();
+        assert_eq!(histograms[0].elements, HashMap::from([('N', 2), ('C', 1), ('B', 1)]));
+        assert_eq!(histograms[1].pairs[&('C', 'H')], 1);
+        assert_eq!(histograms[1].pairs.values().sum::<u64>(), 6);
+        assert_eq!(histograms[10].step, 10);
+        assert_eq!(histograms[10].elements[&'B'], 1749);
+        assert_eq!(histograms[10].elements[&'H'], 161);
+        assert_eq!(histograms[10].spread(), 1588);
+    }
+
+    #[test]
+    fn test_analyze_rules() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        assert_eq!(analyze_rules(&template, &rules, 100, 1e-9).converged_at, None);
+        let report = analyze_rules(&template, &rules, 400, 1e-9);
+        assert!(report.missing_rules.is_empty());
+        assert!(report.unproducible.is_empty());
+        assert!(report.converged_at.unwrap() > 100);
+        // the shares only change in the fourth decimal after 60 steps
+        let histogram = Polymerization::new(&template, &rules).nth(60).unwrap();
+        let b = histogram.elements[&'B'] as f64 / (3.0 * 2f64.powi(60) + 1.0);
+        assert!((report.ratios[&'B'] - b).abs() < 1e-3);
+        // X only shows up in a rule for a pair that never occurs, and AB has no rule
+        let (template, rules) = read_input("AAB\n\nAA -> A\nXA -> X\n").unwrap();
+        let report = analyze_rules(&template, &rules, 10, 1e-9);
+        assert_eq!(report.missing_rules, vec![('A', 'B')]);
+        assert_eq!(report.unproducible, vec!['X']);
+        assert_eq!(report.converged_at, None);
+        let histogram = Polymerization::new(&template, &rules).nth(3).unwrap();
+        assert_eq!(histogram.elements, HashMap::from([('A', 9), ('B', 1)]));
+        // a single element has no pairs to grow from
+        let (template, rules) = read_input("N\n\nNN -> C\n").unwrap();
+        let report = analyze_rules(&template, &rules, 10, 1e-9);
+        assert!(report.missing_rules.is_empty());
+        assert_eq!(report.unproducible, vec!['C']);
+        assert_eq!(report.converged_at, Some(0));
+        assert_eq!(report.ratios, HashMap::from([('N', 1.0), ('C', 0.0)]));
+    }
+}
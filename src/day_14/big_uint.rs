@@ -0,0 +1,128 @@
+use std::{cmp::Ordering, fmt::Display, ops::{Add, AddAssign, Sub}};
+
+const BASE: u32 = 1_000_000_000;
+
+/// A non-negative integer of any size, for counts that don't fit into a `u64`. Only
+/// supports what counting needs: adding, subtracting, comparing and printing.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BigUint {
+    /// Digits in base 10^9, least significant first, without leading zeros.
+    digits: Vec<u32>
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The value if it fits into a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        self.digits.iter().rev().try_fold(0u64, |value, digit| value.checked_mul(BASE as u64)?.checked_add(*digit as u64))
+    }
+
+    fn trim(&mut self) {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut value: u64) -> BigUint {
+        let mut digits = vec![];
+        while value > 0 {
+            digits.push((value % BASE as u64) as u32);
+            value /= BASE as u64;
+        }
+        BigUint { digits }
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.digits.len() < other.digits.len() {
+            self.digits.resize(other.digits.len(), 0);
+        }
+        let mut carry = 0;
+        for (i, digit) in self.digits.iter_mut().enumerate() {
+            let sum = *digit + other.digits.get(i).copied().unwrap_or(0) + carry;
+            *digit = sum % BASE;
+            carry = sum / BASE;
+        }
+        if carry > 0 {
+            self.digits.push(carry);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: &BigUint) -> BigUint {
+        self += other;
+        self
+    }
+}
+
+/// Panics if `other` is larger, as the result would be negative.
+impl Sub<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn sub(mut self, other: &BigUint) -> BigUint {
+        assert!(self >= *other, "subtraction would be negative");
+        let mut borrow = 0;
+        for (i, digit) in self.digits.iter_mut().enumerate() {
+            let subtrahend = other.digits.get(i).copied().unwrap_or(0) + borrow;
+            (*digit, borrow) = if *digit >= subtrahend { (*digit - subtrahend, 0) } else { (*digit + BASE - subtrahend, 1) };
+        }
+        self.trim();
+        self
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.digits.len().cmp(&other.digits.len()).then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.digits.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+                rest.iter().rev().try_for_each(|digit| write!(f, "{digit:09}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        let sum = max.clone() + &max + &BigUint::from(2);
+        assert_eq!(sum.to_string(), "36893488147419103232");
+        assert_eq!(sum.to_u64(), None);
+        assert!(sum > max);
+        assert_eq!((sum - &max - &max).to_u64(), Some(2));
+        assert_eq!((BigUint::from(1_000_000_000) - &BigUint::from(1)).to_string(), "999999999");
+        assert!(BigUint::from(5).cmp(&BigUint::from(7)).is_lt());
+        assert!((BigUint::from(7) - &BigUint::from(7)).is_zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+    }
+}
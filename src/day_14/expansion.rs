@@ -0,0 +1,160 @@
+use std::collections::LinkedList;
+
+use super::{PairSpace, Rules};
+
+/// Finds elements of the polymer after a number of steps without building it, by knowing
+/// how long the part growing out of each pair gets. Looking up an element takes O(steps).
+/// The lengths stop changing once they saturate, which takes less than 64 steps for pairs
+/// that double, so the memory is O(pairs · min(steps, 64)) unless a pair only grows by a
+/// few elements per step.
+pub struct Expansion<'a> {
+    space: PairSpace,
+    rules: &'a Rules,
+    template: Vec<char>,
+    steps: usize,
+    /// `lengths[d][pair]` is the number of elements the pair grows into after `d` steps,
+    /// leaving out its second element, which is the first of the next pair. Saturates at
+    /// `u64::MAX`. Ends at the first step where nothing changed, which holds for all
+    /// later steps as well.
+    lengths: Vec<Vec<u64>>
+}
+
+impl<'a> Expansion<'a> {
+    pub fn new(template: &LinkedList<char>, rules: &'a Rules, steps: usize) -> Expansion<'a> {
+        let space = PairSpace::new(template, rules);
+        let mut lengths: Vec<Vec<u64>> = vec![vec![1; space.pair_count()]];
+        for step in 1..=steps {
+            let previous = &lengths[step - 1];
+            let current: Vec<u64> = (0..space.pair_count())
+                .map(|index| match children(&space, rules, index) {
+                    Some((left, right)) => previous[left].saturating_add(previous[right]),
+                    None => 1
+                })
+                .collect();
+            if current == *previous {
+                break;
+            }
+            lengths.push(current);
+        }
+        Expansion { space, rules, template: template.iter().copied().collect(), steps, lengths }
+    }
+
+    /// Number of elements of the polymer, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.template
+            .windows(2)
+            .map(|pair| self.lengths(self.steps)[self.space.index((pair[0], pair[1]))])
+            .fold(1, u64::saturating_add)
+    }
+
+    /// The length of each pair's part after `depth` steps.
+    fn lengths(&self, depth: usize) -> &[u64] {
+        &self.lengths[depth.min(self.lengths.len() - 1)]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.template.is_empty()
+    }
+
+    /// The element at index `k` of the polymer, `None` if it isn't that long.
+    pub fn char_at(&self, mut k: u64) -> Option<char> {
+        for pair in self.template.windows(2) {
+            let index = self.space.index((pair[0], pair[1]));
+            let length = self.lengths(self.steps)[index];
+            if k < length {
+                return Some(self.descend(index, self.steps, k));
+            }
+            k -= length;
+        }
+        (k == 0).then(|| *self.template.last().unwrap())
+    }
+
+    /// Follows the pair down to the step where element `k` of its part gets inserted.
+    fn descend(&self, mut index: usize, mut depth: usize, mut k: u64) -> char {
+        while depth > 0 {
+            let Some((left, right)) = children(&self.space, self.rules, index) else {
+                break;
+            };
+            depth -= 1;
+            let length = self.lengths(depth)[left];
+            if k < length {
+                index = left;
+            } else {
+                k -= length;
+                index = right;
+            }
+        }
+        self.space.pair(index).0
+    }
+
+    /// The elements from index `k` up to `k + n`, fewer if the polymer ends before that.
+    pub fn substring(&self, k: u64, n: u64) -> String {
+        (k..k.saturating_add(n)).map_while(|i| self.char_at(i)).collect()
+    }
+}
+
+/// The two pairs a pair splits into, `None` if there's no rule for it.
+fn children(space: &PairSpace, rules: &Rules, index: usize) -> Option<(usize, usize)> {
+    let (a, b) = space.pair(index);
+    let insert = *rules.get(&a)?.get(&b)?;
+    Some((space.index((a, insert)), space.index((insert, b))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::day_14::read_input;
+
+    #[test]
+    fn test_substring() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        let polymers = ["NNCB", "NCNBCHB", "NBCCNBBBCBHCB", "NBBBCNCCNBBNBNBBCHBHHBCHB", "NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB"];
+        for (steps, polymer) in polymers.iter().enumerate() {
+            let expansion = Expansion::new(&template, &rules, steps);
+            assert_eq!(expansion.len(), polymer.len() as u64);
+            assert_eq!(expansion.substring(0, 100), *polymer);
+        }
+        let expansion = Expansion::new(&template, &rules, 4);
+        assert_eq!(expansion.substring(10, 5), "NBCNC");
+        assert_eq!(expansion.char_at(48), Some('B'));
+        assert_eq!(expansion.char_at(49), None);
+        let mut polymer: Vec<char> = template.iter().copied().collect();
+        for _ in 0..12 {
+            let inserts = polymer.windows(2).map(|pair| rules[&pair[0]][&pair[1]]);
+            polymer = polymer.iter().copied().zip(inserts).flat_map(|(a, insert)| [a, insert]).chain(polymer.last().copied()).collect();
+        }
+        let expansion = Expansion::new(&template, &rules, 12);
+        assert_eq!(expansion.substring(0, u64::MAX), polymer.iter().collect::<String>());
+        assert_eq!(expansion.substring(5000, 30), polymer[5000..5030].iter().collect::<String>());
+        let expansion = Expansion::new(&template, &rules, 40);
+        assert_eq!(expansion.len(), 3 * (1 << 40) + 1);
+        assert_eq!(expansion.char_at(3 * (1 << 40)), Some('B'));
+        assert_eq!(Expansion::new(&template, &rules, 100).len(), u64::MAX);
+    }
+
+    #[test]
+    fn test_many_steps() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        let steps = 1_000_003;
+        let expansion = Expansion::new(&template, &rules, steps);
+        // every pair doubles, so the lengths saturate within 64 steps
+        assert!(expansion.lengths.len() <= 65);
+        assert_eq!(expansion.len(), u64::MAX);
+        // the first elements only depend on the first elements of the step before
+        let mut prefix: Vec<char> = template.iter().copied().collect();
+        for _ in 0..steps {
+            let inserts = prefix.windows(2).map(|pair| rules[&pair[0]][&pair[1]]);
+            prefix = prefix.iter().copied().zip(inserts).flat_map(|(a, insert)| [a, insert]).take(4).collect();
+        }
+        assert_eq!(expansion.substring(0, 4), prefix.iter().collect::<String>());
+        assert!(expansion.char_at(u64::MAX / 2).is_some());
+        // a pair without a rule never grows, and the other one only by one element per step
+        let (template, rules) = read_input("AB\n\nAB -> A\n").unwrap();
+        let expansion = Expansion::new(&template, &rules, 100_000);
+        assert_eq!(expansion.len(), 100_002);
+        assert_eq!(expansion.char_at(100_001), Some('B'));
+        assert_eq!(expansion.char_at(100_002), None);
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::{HashMap, LinkedList}, ops::Mul};
+
+use super::{BigUint, Rules};
+
+/// Numbers all pairs of the elements found in a template and its rules, so the count of
+/// each pair in a polymer fits into a vector.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PairSpace {
+    /// Sorted, pair (a, b) has index `index(a) * len + index(b)`.
+    elements: Vec<char>
+}
+
+impl PairSpace {
+    pub fn new(template: &LinkedList<char>, rules: &Rules) -> PairSpace {
+        let mut elements: Vec<char> = template
+            .iter()
+            .copied()
+            .chain(rules.iter().flat_map(|(first, inserts)| inserts.iter().flat_map(move |(second, insert)| [*first, *second, *insert])))
+            .collect();
+        elements.sort();
+        elements.dedup();
+        PairSpace { elements }
+    }
+
+    pub fn elements(&self) -> &[char] {
+        &self.elements
+    }
+
+    pub fn pair_count(&self) -> usize {
+        self.elements.len() * self.elements.len()
+    }
+
+    pub fn index(&self, (a, b): (char, char)) -> usize {
+        let position = |element| self.elements.binary_search(&element).expect("element of the pair space");
+        position(a) * self.elements.len() + position(b)
+    }
+
+    pub fn pair(&self, index: usize) -> (char, char) {
+        (self.elements[index / self.elements.len()], self.elements[index % self.elements.len()])
+    }
+
+    /// Indices of the pairs a pair turns into in one step. A pair without a rule stays.
+    pub fn successors(&self, index: usize, rules: &Rules) -> Vec<usize> {
+        let (a, b) = self.pair(index);
+        match rules.get(&a).and_then(|inserts| inserts.get(&b)) {
+            Some(insert) => vec![self.index((a, *insert)), self.index((*insert, b))],
+            None => vec![index]
+        }
+    }
+
+    /// How often each pair occurs in the template.
+    pub fn initial_counts(&self, template: &LinkedList<char>) -> Vec<u64> {
+        let mut counts = vec![0; self.pair_count()];
+        for (a, b) in template.iter().zip(template.iter().skip(1)) {
+            counts[self.index((*a, *b))] += 1;
+        }
+        counts
+    }
+
+    /// Element counts from pair counts: every element is the first of a pair, except for
+    /// the last one of the polymer, which never changes.
+    fn element_counts<T: Clone>(&self, pair_counts: &[T], last: char, one: T, add: impl Fn(&mut T, &T)) -> HashMap<char, T> {
+        let mut counts: HashMap<char, T> = HashMap::new();
+        for (index, count) in pair_counts.iter().enumerate() {
+            let first = self.pair(index).0;
+            match counts.get_mut(&first) {
+                Some(total) => add(total, count),
+                None => { counts.insert(first, count.clone()); }
+            }
+        }
+        add(counts.get_mut(&last).expect("last element in the pair space"), &one);
+        counts
+    }
+}
+
+/// A square matrix with entries modulo `modulus`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ModMatrix {
+    size: usize,
+    modulus: u64,
+    cells: Vec<u64>
+}
+
+impl ModMatrix {
+    pub fn identity(size: usize, modulus: u64) -> ModMatrix {
+        let mut cells = vec![0; size * size];
+        for i in 0..size {
+            cells[i * size + i] = 1 % modulus;
+        }
+        ModMatrix { size, modulus, cells }
+    }
+
+    /// The matrix turning the pair counts of a polymer into those after one more step:
+    /// entry (to, from) is how many pairs `to` a single pair `from` turns into.
+    pub fn transition(space: &PairSpace, rules: &Rules, modulus: u64) -> ModMatrix {
+        let size = space.pair_count();
+        let mut cells = vec![0; size * size];
+        for from in 0..size {
+            for to in space.successors(from, rules) {
+                cells[to * size + from] = (cells[to * size + from] + 1) % modulus;
+            }
+        }
+        ModMatrix { size, modulus, cells }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u64 {
+        self.cells[row * self.size + col]
+    }
+
+    /// The matrix to the power of `exp`, by repeated squaring.
+    pub fn pow(&self, mut exp: u64) -> ModMatrix {
+        let mut result = ModMatrix::identity(self.size, self.modulus);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    pub fn mul_vec(&self, v: &[u64]) -> Vec<u64> {
+        (0..self.size)
+            .map(|row| (0..self.size).fold(0, |sum, col| mul_add(self.get(row, col), v[col] % self.modulus, sum, self.modulus)))
+            .collect()
+    }
+}
+
+/// `a * b + c` modulo `modulus`, without overflowing.
+fn mul_add(a: u64, b: u64, c: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128 + c as u128) % modulus as u128) as u64
+}
+
+impl Mul for &ModMatrix {
+    type Output = ModMatrix;
+
+    fn mul(self, other: &ModMatrix) -> ModMatrix {
+        let size = self.size;
+        let mut cells = vec![0; size * size];
+        for row in 0..size {
+            for k in (0..size).filter(|k| self.get(row, *k) != 0) {
+                let a = self.get(row, k);
+                for col in 0..size {
+                    cells[row * size + col] = mul_add(a, other.get(k, col), cells[row * size + col], self.modulus);
+                }
+            }
+        }
+        ModMatrix { size, modulus: self.modulus, cells }
+    }
+}
+
+/// Count of each element after any number of steps, like 10^18, modulo `modulus`. Raises
+/// the pair transition matrix to the power of `steps`, which takes O(pairs³ · log steps).
+pub fn element_counts_mod(steps: u64, template: &LinkedList<char>, rules: &Rules, modulus: u64) -> HashMap<char, u64> {
+    let space = PairSpace::new(template, rules);
+    let pair_counts = ModMatrix::transition(&space, rules, modulus).pow(steps).mul_vec(&space.initial_counts(template));
+    let last = *template.back().expect("a non-empty template");
+    space.element_counts(&pair_counts, last, 1, |total, count| *total = (*total + count) % modulus)
+}
+
+/// Exact count of each element after a number of steps. The counts roughly double with
+/// every step, so this works for thousands of steps, but not for millions.
+pub fn element_counts_exact(steps: usize, template: &LinkedList<char>, rules: &Rules) -> HashMap<char, BigUint> {
+    let space = PairSpace::new(template, rules);
+    let successors: Vec<Vec<usize>> = (0..space.pair_count()).map(|index| space.successors(index, rules)).collect();
+    let mut pair_counts: Vec<BigUint> = space.initial_counts(template).into_iter().map(BigUint::from).collect();
+    for _ in 0..steps {
+        let mut next = vec![BigUint::zero(); space.pair_count()];
+        for (index, count) in pair_counts.iter().enumerate().filter(|(_, count)| !count.is_zero()) {
+            for successor in &successors[index] {
+                next[*successor] += count;
+            }
+        }
+        pair_counts = next;
+    }
+    let last = *template.back().expect("a non-empty template");
+    space.element_counts(&pair_counts, last, BigUint::from(1), |total, count| *total += count)
+}
+
+/// Like `polymer_fast`, the count of the most minus the least common element, but exact
+/// for any number of steps. Elements that don't occur in the polymer aren't counted.
+pub fn polymer_exact(steps: usize, template: &LinkedList<char>, rules: &Rules) -> BigUint {
+    let counts: Vec<BigUint> = element_counts_exact(steps, template, rules).into_values().filter(|count| !count.is_zero()).collect();
+    let max = counts.iter().max().cloned().unwrap_or_default();
+    let min = counts.iter().min().cloned().unwrap_or_default();
+    max - &min
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::day_14::{polymer_fast, read_input};
+
+    #[test]
+    fn test_transition() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        let space = PairSpace::new(&template, &rules);
+        assert_eq!(space.elements(), &['B', 'C', 'H', 'N']);
+        let nn = space.index(('N', 'N'));
+        assert_eq!(space.pair(nn), ('N', 'N'));
+        // NN -> C
+        let matrix = ModMatrix::transition(&space, &rules, 1_000_000_007);
+        assert_eq!(matrix.get(space.index(('N', 'C')), nn), 1);
+        assert_eq!(matrix.get(space.index(('C', 'N')), nn), 1);
+        assert_eq!(matrix.pow(0), ModMatrix::identity(16, 1_000_000_007));
+        assert_eq!(matrix.pow(5), &(&matrix.pow(2) * &matrix.pow(2)) * &matrix);
+    }
+
+    #[test]
+    fn test_element_counts() {
+        let (template, rules) = read_input(&fs::read_to_string("./input/day_14.test.txt").unwrap()).unwrap();
+        let exact = element_counts_exact(10, &template, &rules);
+        assert_eq!(exact[&'B'], BigUint::from(1749));
+        assert_eq!(exact[&'H'], BigUint::from(161));
+        let exact = element_counts_exact(40, &template, &rules);
+        assert_eq!(exact[&'B'], BigUint::from(2192039569602));
+        let counts = element_counts_mod(40, &template, &rules, 1_000_000_007);
+        assert_eq!(counts[&'B'], 2192039569602 % 1_000_000_007);
+        assert_eq!(counts[&'H'], 3849876073 % 1_000_000_007);
+        assert_eq!(polymer_exact(40, &template, &rules).to_u64(), Some(polymer_fast(40, &template, &rules)));
+        assert!(polymer_exact(200, &template, &rules).to_string().len() > 50);
+        // the polymer's length after s steps is 3 * 2^s + 1, and 2^(p-1) = 1 modulo a prime p
+        let total: u64 = element_counts_mod(1_000_000_006, &template, &rules, 1_000_000_007).values().sum();
+        assert_eq!(total % 1_000_000_007, 4);
+        assert_eq!(element_counts_mod(1_000_000_000_000_000_000, &template, &rules, 998_244_353).len(), 4);
+    }
+}
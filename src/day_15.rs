@@ -1,211 +1,479 @@
-use std::{fs, collections::{HashMap, HashSet}};
+//! Day 15: Chiton. Finds the path with the lowest total risk through a cave, from the
+//! top left to the bottom right.
 
-pub fn part_1() {
-    println!("fastest path is {} scary", find_fastest_path("./input/day_15.txt"));
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet}, fs, hint::black_box, io, path::Path, time::Instant};
+
+use crate::{solution::Solution, error::{ParseError, ParseContext}, grid::{Adjacency, Grid, Point}, log::debug, bench::Stats, pathfinder::{self, Cost, Enter, Pathfinder}};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    type Input = Grid<u8>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_input(input)
+    }
+    fn part_1(input: &Self::Input) -> u64 {
+        find_fastest_path(input)
+    }
+    fn part_2(input: &Self::Input) -> u64 {
+        find_in_bigger_path(input)
+    }
 }
-pub fn part_2() {
-    println!("fastest path is {} scary", find_in_bigger_path("./input/day_15.txt"));
+
+/// Reads the risk level of each position, row by row.
+pub fn read_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    let ctx = ParseContext::new(Day15::DAY, input);
+    if input.trim().is_empty() {
+        return Err(ctx.unexpected(input.trim(), "a map of risk levels"));
+    }
+    Grid::parse(input.trim(), &ctx, "a risk level from 0 to 9", |c| c.to_digit(10).map(|risk| risk as u8))
 }
 
-#[derive(Hash, PartialEq, Eq, Debug)]
-struct Vertex {
-    coords: (usize, usize),
-    risk: u8,
-    dist: Option<u64>
+/// What happens to risk levels that grow too high when the cave gets tiled.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Wrap {
+    /// Levels above `max` start over at `min`, like 9 wrapping around to 1 in the puzzle.
+    Cycle { min: u8, max: u8 },
+    /// Levels above the limit stay at it.
+    Clamp(u8)
 }
 
-fn print_vertices(vertices: &HashMap<(usize, usize), Vertex>) {
-    for y in 0..50 {
-        println!("");
-        for x in 0..50 {
-            print!("{}", vertices.get(&(x, y)).unwrap().risk);
+impl Wrap {
+    pub fn apply(self, risk: u64) -> u8 {
+        match self {
+            Wrap::Cycle { min, max } if risk > max as u64 => (min as u64 + (risk - min as u64) % (max as u64 - min as u64 + 1)) as u8,
+            Wrap::Clamp(max) if risk > max as u64 => max,
+            _ => risk as u8
         }
     }
-    println!("");
-}
-
-fn find_in_bigger_path(file: &str) -> u64 {
-    let input = fs::read_to_string(file).unwrap();
-    let input = input
-        .trim()
-        .split("\n")
-        .map(|line| line
-            .split("")
-            .filter_map(|c| c.parse::<u8>().ok()));
-    
-    let vertices: Vec<Vertex> = input
-        .enumerate()
-        .map(|(y, line)| line
-            .enumerate()
-            .map(move |(x, risk)| {
-                Vertex {
-                    coords: (x, y),
-                    risk,
-                    dist: if x == 0 && y == 0 { Some(0) } else { None }
-                }
-            }))
-        .flatten()
-        .collect();
-    let size = (vertices.len() as f64).sqrt() as usize;
-    let mut vertices: HashMap<(usize, usize), Vertex> = 
-        vertices
-        .into_iter()
-        .fold(HashMap::<(usize, usize), Vertex>::new(), 
-        |mut map, vertex| {
-            let (vx, vy) = vertex.coords;
-            let risk = vertex.risk;
-            for x in 0..5u8 {
-                for y in 0..5u8 {
-                    let new_coords = (
-                        vx + size * x as usize,
-                        vy + size * y as usize);
-                    let new_risk = risk + x + y;
-                    map
-                        .entry(new_coords)
-                        .or_insert(Vertex {
-                            coords: new_coords,
-                            dist: if new_coords.0 == 0 && new_coords.1 == 0 { Some(0) } else { None },
-                            risk: if new_risk > 9 { new_risk % 9 } else { new_risk }
-                        });
-                }
+}
+
+/// How the full cave is made from the tile of the input: repeated `across` times to the
+/// right and `down` times downwards, with the risk levels increasing by `increase` per
+/// repetition in either direction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Tiling {
+    pub across: usize,
+    pub down: usize,
+    pub increase: u8,
+    pub wrap: Wrap
+}
+
+impl Tiling {
+    pub fn new(across: usize, down: usize) -> Tiling {
+        Tiling { across, down, ..Tiling::default() }
+    }
+}
+
+/// The puzzle's tiling: 5 times in both directions, wrapping around from 9 to 1.
+impl Default for Tiling {
+    fn default() -> Tiling {
+        Tiling { across: 5, down: 5, increase: 1, wrap: Wrap::Cycle { min: 1, max: 9 } }
+    }
+}
+
+/// The full cave of the puzzle, see [`Tiling::default`].
+pub fn expand_cave(tile: &Grid<u8>) -> Grid<u8> {
+    tile_cave(tile, &Tiling::default())
+}
+
+/// The full cave made of copies of `tile`. The tile doesn't need to be square.
+pub fn tile_cave(tile: &Grid<u8>, tiling: &Tiling) -> Grid<u8> {
+    assert!(tiling.across > 0 && tiling.down > 0, "the tiling needs at least one copy in each direction");
+    assert!(!matches!(tiling.wrap, Wrap::Cycle { min, max } if min > max), "the wrap cycle needs min <= max");
+    let (width, height) = (tile.width() as i32, tile.height() as i32);
+    let mut cave = Grid::new(tile.width() * tiling.across, tile.height() * tiling.down, 0);
+    for (x, y) in cave.points() {
+        let repetitions = (x / width + y / height) as u64;
+        let risk = *tile.at((x % width, y % height)) as u64 + repetitions * tiling.increase as u64;
+        cave.set((x, y), tiling.wrap.apply(risk));
+    }
+    cave
+}
+
+/// Lowest total risk through the full cave, see [`expand_cave`].
+pub fn find_in_bigger_path(input: &Grid<u8>) -> u64 {
+    find_in_tiled_path(input, &Tiling::default())
+}
+
+/// Lowest total risk through the cave made of copies of `input`, see [`tile_cave`].
+pub fn find_in_tiled_path(input: &Grid<u8>, tiling: &Tiling) -> u64 {
+    let cave = tile_cave(input, tiling);
+    // the top left corner where the first copies meet, which is enough to check the tiling
+    let shown = ((2 * input.width()).min(cave.width()) as i32 - 1, (2 * input.height()).min(cave.height()) as i32 - 1);
+    debug!("{}", cave.render_area((0, 0), shown, |risk| char::from(b'0' + risk)));
+    find_fastest_path(&cave)
+}
+
+/// Lowest total risk through the given cave, see [`find_fastest_route`].
+pub fn find_fastest_path(input: &Grid<u8>) -> u64 {
+    find_fastest_route(input).risk
+}
+
+/// A path through the cave and its total risk, which leaves out the starting position.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Route {
+    pub risk: u64,
+    /// Every position on the way, from the top left to the bottom right.
+    pub path: Vec<Point>
+}
+
+impl Route {
+    /// Follows the predecessors back from the goal.
+    fn trace(risk: u64, previous: &Grid<Option<Point>>, goal: Point) -> Route {
+        let mut path = vec![goal];
+        while let Some(vertex) = previous.at(*path.last().unwrap()) {
+            path.push(*vertex);
+        }
+        path.reverse();
+        Route { risk, path }
+    }
+}
+
+/// The route with the lowest total risk, by Dijkstra's algorithm with a binary heap: the
+/// A* search of [`find_route_guided`] without an estimate.
+pub fn find_fastest_route(input: &Grid<u8>) -> Route {
+    find_route_guided(input, |_| 0)
+}
+
+/// Like [`find_fastest_route`], but A* with the least risk left to the goal, which is at
+/// least the Manhattan distance times the lowest risk in the cave.
+pub fn find_fastest_route_a_star(input: &Grid<u8>) -> Route {
+    let goal = input.max();
+    let min_risk = input.iter().map(|(_, risk)| *risk as u64).min().unwrap_or(0);
+    find_route_guided(input, |(x, y)| ((goal.0 - x) + (goal.1 - y)) as u64 * min_risk)
+}
+
+/// A*: vertices are visited in the order of their risk so far plus `estimate`'s guess of
+/// the risk left to the goal, which must never be more than the actual risk.
+fn find_route_guided(input: &Grid<u8>, estimate: impl Fn(Point) -> u64) -> Route {
+    let goal = input.max();
+    let mut dists = input.map(|_| u64::MAX);
+    let mut previous: Grid<Option<Point>> = input.map(|_| None);
+    let mut queue = BinaryHeap::from([Reverse((estimate((0, 0)), 0, (0, 0)))]);
+    dists.set((0, 0), 0);
+    while let Some(Reverse((_, dist, vertex))) = queue.pop() {
+        if vertex == goal {
+            return Route::trace(dist, &previous, goal);
+        }
+        // a stale entry, the vertex was reached cheaper since
+        if dist > *dists.at(vertex) {
+            continue;
+        }
+        for neighbor in input.neighbors(vertex, Adjacency::Orthogonal) {
+            let sum = dist + *input.at(neighbor) as u64;
+            if sum < *dists.at(neighbor) {
+                dists.set(neighbor, sum);
+                previous.set(neighbor, Some(vertex));
+                queue.push(Reverse((sum + estimate(neighbor), sum, neighbor)));
             }
-            map
-        });
-    print_vertices(&vertices);
-    let total_size = size * size * 25;
-    let mut vertices_with_dist = HashMap::new();
+        }
+    }
+    panic!("Didn't find a path to {goal:?}")
+}
+
+/// The cheapest route through the cave under other rules for moving and for what moves
+/// cost, see [`Pathfinder`]. `None` if the rules don't allow reaching the bottom right.
+pub fn find_route_with<C: Cost<u8>>(input: &Grid<u8>, pathfinder: &Pathfinder<C>) -> Option<Route> {
+    pathfinder
+        .route(input, (0, 0), input.max())
+        .map(|route| Route { risk: route.cost, path: route.path })
+}
+
+/// The cheapest route from any of the starts to any of the goals, moving and paying like
+/// in the puzzle.
+pub fn find_route_between(input: &Grid<u8>, starts: &[Point], goals: &[Point]) -> Option<Route> {
+    Pathfinder::new(Enter)
+        .route_between(input, starts, goals)
+        .map(|route| Route { risk: route.cost, path: route.path })
+}
+
+/// The `k` routes with the lowest total risk from the top left to the bottom right that
+/// don't visit a position twice. How close their risks are shows how much the answer
+/// depends on single risk levels.
+pub fn find_fastest_routes(input: &Grid<u8>, k: usize) -> Vec<Route> {
+    Pathfinder::new(Enter)
+        .k_routes(input, (0, 0), input.max(), k)
+        .into_iter()
+        .map(|route| Route { risk: route.cost, path: route.path })
+        .collect()
+}
+
+/// The lowest total risk from the top left to every position.
+pub fn risk_map(input: &Grid<u8>) -> Grid<Option<u64>> {
+    Pathfinder::new(Enter).distances(input, &[(0, 0)])
+}
+
+/// Writes [`risk_map`] to `path` as comma separated values.
+pub fn write_risk_map(input: &Grid<u8>, path: &Path) -> io::Result<()> {
+    fs::write(path, pathfinder::distances_to_csv(&risk_map(input)))
+}
+
+/// The original search, which looks for the closest vertex by scanning all vertices that
+/// have a distance but aren't done yet. Kept to compare the others against.
+pub fn find_fastest_route_scan(input: &Grid<u8>) -> Route {
+    let goal = input.max();
+    let mut dists: Grid<Option<u64>> = input.map(|_| None);
+    let mut previous: Grid<Option<Point>> = input.map(|_| None);
+    let mut done = input.map(|_| false);
+    let mut vertices_with_dist: HashMap<Point, u64> = HashMap::new();
+    dists.set((0, 0), Some(0));
     vertices_with_dist.insert((0, 0), 0);
-    let mut spt_set = HashMap::new();
-    while spt_set.len() < total_size {
-        println!("{}/{}", spt_set.len(), total_size);
-        // find vertex with Some(dist) with lowest dist
-        let vertex = vertices_with_dist
-            .iter()
-            .min_by(|a, b| a.1.cmp(b.1));
-        let vertex = match vertex {
-            Some(((x, y), _)) => vertices.get(&(*x, *y)).unwrap(),
-            None => panic!("Didn't find a vertex that isn't part of spt_set and has a dist! set is {:?}",
-                spt_set.len())
-        };
-        let vertex_dist = vertex.dist.unwrap();
-        let v_coords = vertex.coords;
-        let (x , y) = v_coords;
-        vertices_with_dist.remove(&v_coords);
-        let v = vertices.remove(&v_coords).unwrap();
-        spt_set.insert(v_coords, v);
-        [
-            (Some(x + 1), Some(y)),
-            (usize::checked_sub(x, 1), Some(y)),
-            (Some(x), Some(y + 1)),
-            (Some(x), usize::checked_sub(y, 1))]
-            .into_iter()
-            .filter(|(x, y)| x.is_some() && y.is_some())
-            .map(|(x, y)| (x.unwrap(), y.unwrap()))
-            .for_each(|(x, y)| {
-                let v = vertices
-                    .get_mut(&(x, y));
-                let v = match v {
-                    None => return,
-                    Some(v) => v
-                };
-                let sum = vertex_dist + v.risk as u64;
-                let new_dist = match v.dist {
-                    None => sum,
-                    Some(dist) if sum < dist => sum,
-                    Some(dist) => dist
-                };
-                v.dist = Some(new_dist);
-                vertices_with_dist.insert((x, y), new_dist);
-            });
-        if v_coords.0 == size * 5 - 1 && v_coords.1 == size * 5 - 1 {
-            break;
+    // find vertex with a dist that isn't done yet with lowest dist
+    while let Some((&vertex, &vertex_dist)) = vertices_with_dist.iter().min_by_key(|(_, dist)| **dist) {
+        if vertex == goal {
+            return Route::trace(vertex_dist, &previous, goal);
+        }
+        vertices_with_dist.remove(&vertex);
+        done.set(vertex, true);
+        for neighbor in input.neighbors(vertex, Adjacency::Orthogonal) {
+            if *done.at(neighbor) {
+                continue;
+            }
+            let sum = vertex_dist + *input.at(neighbor) as u64;
+            if dists.at(neighbor).is_none_or(|dist| sum < dist) {
+                dists.set(neighbor, Some(sum));
+                previous.set(neighbor, Some(vertex));
+                vertices_with_dist.insert(neighbor, sum);
+                debug!("Adjacent to {}, {}: {}", vertex.0, vertex.1, sum);
+            }
         }
     }
-    spt_set.get(&(size * 5 - 1, size * 5 - 1)).unwrap().dist.unwrap()
-}
-
-fn find_fastest_path(file: &str) -> u64 {
-    let input = fs::read_to_string(file).unwrap();
-    let input = input
-        .trim()
-        .split("\n")
-        .map(|line| line
-            .split("")
-            .filter_map(|c| c.parse::<u8>().ok()));
-    
-    let mut graph: Vec<Vec<Vertex>> = input
-        .enumerate()
-        .map(|(y, line)| line
-            .enumerate()
-            .map(|(x, risk)| Vertex {
-                coords: (x, y),
-                risk,
-                dist: if x == 0 && y == 0 { Some(0) } else { None }
-            })
-            .collect())
+    panic!("Didn't find a path to {goal:?}")
+}
+
+/// The ways to find the lowest total risk through a cave.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Solver {
+    Scan,
+    Dijkstra,
+    AStar
+}
+
+impl Solver {
+    pub const ALL: [Solver; 3] = [Solver::Scan, Solver::Dijkstra, Solver::AStar];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Solver::Scan => "scan",
+            Solver::Dijkstra => "dijkstra",
+            Solver::AStar => "a*"
+        }
+    }
+
+    pub fn solve(self, cave: &Grid<u8>) -> Route {
+        match self {
+            Solver::Scan => find_fastest_route_scan(cave),
+            Solver::Dijkstra => find_fastest_route(cave),
+            Solver::AStar => find_fastest_route_a_star(cave)
+        }
+    }
+}
+
+/// Times every solver `runs` times on the same cave. Returns the lowest total risk each
+/// of them found, which should all be the same, and the stats of their durations.
+pub fn compare_solvers(cave: &Grid<u8>, runs: usize) -> Vec<(Solver, u64, Stats)> {
+    Solver::ALL
+        .iter()
+        .map(|solver| {
+            let mut risk = 0;
+            let times: Vec<_> = (0..runs)
+                .map(|_| {
+                    let start = Instant::now();
+                    risk = black_box(solver.solve(black_box(cave))).risk;
+                    start.elapsed()
+                })
+                .collect();
+            (*solver, risk, Stats::from(&times))
+        })
+        .collect()
+}
+
+/// The risk map with the path on it: in red if `highlight` is set, for the terminal, or
+/// else as the only risks shown, with dots everywhere else, for files to diff.
+pub fn render_route(cave: &Grid<u8>, path: &[Point], highlight: bool) -> String {
+    let on_path: HashSet<Point> = path.iter().copied().collect();
+    let mut out = String::new();
+    for y in 0..cave.height() as i32 {
+        for x in 0..cave.width() as i32 {
+            let risk = char::from(b'0' + cave.at((x, y)));
+            match (on_path.contains(&(x, y)), highlight) {
+                (true, true) => out += &format!("\x1b[1;31m{risk}\x1b[0m"),
+                (false, false) => out.push('.'),
+                _ => out.push(risk)
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Size of a position in the SVG, in pixels.
+const SVG_CELL: usize = 8;
+
+/// The risk map as an SVG image, darker for higher risks, with the path drawn over it.
+pub fn route_to_svg(cave: &Grid<u8>, path: &[Point]) -> String {
+    let (width, height) = (cave.width() * SVG_CELL, cave.height() * SVG_CELL);
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n");
+    for ((x, y), risk) in cave.iter() {
+        let shade = 255 - 25 * *risk.min(&9) as usize;
+        svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" fill=\"rgb({shade},{shade},{shade})\"/>\n",
+            x as usize * SVG_CELL, y as usize * SVG_CELL);
+    }
+    let points: Vec<String> = path
+        .iter()
+        .map(|(x, y)| format!("{},{}", *x as usize * SVG_CELL + SVG_CELL / 2, *y as usize * SVG_CELL + SVG_CELL / 2))
         .collect();
-    
-    let total_size = graph.len() * graph[0].len();
-    let mut spt_set = HashSet::new();
-    while spt_set.len() < total_size {
-        // find vertex with Some(dist) with lowest dist
-        let vertex = graph
-            .iter()
-            .flatten()
-            .filter(|v| !spt_set.contains(&(v.coords.0, v.coords.1)))
-            .filter(|v| v.dist.is_some())
-            .min_by(|v1, v2| v1.dist.unwrap().cmp(&v2.dist.unwrap()));
-        let vertex = match vertex {
-            Some(v) => v,
-            None => panic!("Didn't find a vertex that isn't part of spt_set and has a dist!")
-        };
-        let vertex_dist = vertex.dist.unwrap();
-        let v_coords = vertex.coords;
-        let (x , y) = v_coords;
-        spt_set.insert((x, y));
-        let adjacent_coords = [
-            (Some(x + 1), Some(y)),
-            (usize::checked_sub(x, 1), Some(y)),
-            (Some(x), Some(y + 1)),
-            (Some(x), usize::checked_sub(y, 1))];
-        adjacent_coords
-            .iter()
-            .filter(|(x, y)| x.is_some() && y.is_some())
-            .map(|(x, y)| (x.unwrap(), y.unwrap()))
-            .filter(|(x, y)| !spt_set.contains(&(*x, *y)))
-            .for_each(|(x, y)| {
-                let v = graph
-                    .get_mut(y)
-                    .and_then(|line| line.get_mut(x));
-                let v = match v {
-                    None => return,
-                    Some(v) => v
-                };
-                let sum = vertex_dist + v.risk as u64;
-                let new_dist = match v.dist {
-                    None => sum,
-                    Some(dist) if sum < dist => sum,
-                    Some(dist) => dist
-                };
-                v.dist = Some(new_dist);
-                println!("Adjacent to {}, {}: {:?}", v_coords.0, v_coords.1, v);
-            });
-    }
-    graph.last().unwrap().last().unwrap().dist.unwrap()
+    svg += &format!("<polyline points=\"{}\" fill=\"none\" stroke=\"red\" stroke-width=\"{}\"/>\n</svg>\n", points.join(" "), SVG_CELL / 4);
+    svg
 }
 
+/// Writes the route to `path`, as SVG if the file name ends in `.svg` and as text else.
+pub fn write_route(cave: &Grid<u8>, route: &Route, path: &Path) -> io::Result<()> {
+    let contents = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => route_to_svg(cave, &route.path),
+        _ => render_route(cave, &route.path, false)
+    };
+    fs::write(path, contents)
+}
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_15::*;
+    use crate::pathfinder::{Enter, Exit, Movement, TurnPenalty};
 
     #[test]
     fn part_1() {
-        assert_eq!(find_fastest_path("./input/day_15.test.txt"), 40);
+        assert_eq!(find_fastest_path(&read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap()), 40);
     }
 
     #[test]
     fn part_2() {
-        assert_eq!(find_in_bigger_path("./input/day_15.test.txt"), 315);
+        assert_eq!(find_in_bigger_path(&read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap()), 315);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(read_input("").is_err());
+        let err = read_input(" \n\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 15, line 1, column 1: expected a map of risk levels, found end of input");
+        assert_eq!(read_input("119\n1x1").unwrap_err().position(), (2, 2));
+    }
+
+    #[test]
+    fn test_tiling() {
+        let tile = read_input("18\n99\n45").unwrap();
+        assert_eq!(expand_cave(&tile).render(|risk| char::from(b'0' + risk)),
+            tile_cave(&tile, &Tiling::new(5, 5)).render(|risk| char::from(b'0' + risk)));
+        let cave = tile_cave(&tile, &Tiling::new(3, 2));
+        assert_eq!((cave.width(), cave.height()), (6, 6));
+        assert_eq!(cave.render(|risk| char::from(b'0' + risk)), "182931\n991122\n455667\n293142\n112233\n566778\n");
+        let clamped = Tiling { increase: 4, wrap: Wrap::Clamp(9), ..Tiling::new(2, 1) };
+        assert_eq!(tile_cave(&tile, &clamped).render(|risk| char::from(b'0' + risk)), "1859\n9999\n4589\n");
+        assert_eq!(Wrap::Cycle { min: 0, max: 4 }.apply(12), 2);
+        assert_eq!(Wrap::Cycle { min: 1, max: 9 }.apply(18), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one copy in each direction")]
+    fn test_empty_tiling() {
+        find_in_tiled_path(&read_input("18\n99").unwrap(), &Tiling::new(3, 0));
+    }
+
+    #[test]
+    fn test_rectangular() {
+        let tile = read_input("1163751\n1381373\n2136511").unwrap();
+        assert_eq!(find_fastest_path(&tile), 20);
+        assert_eq!(find_in_tiled_path(&tile, &Tiling::new(1, 1)), 20);
+        let cave = expand_cave(&tile);
+        assert_eq!((cave.width(), cave.height()), (35, 15));
+        for solver in Solver::ALL {
+            assert_eq!(solver.solve(&cave).risk, find_in_bigger_path(&tile), "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn test_solvers() {
+        let tile = read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap();
+        let cave = expand_cave(&tile);
+        for solver in Solver::ALL {
+            assert_eq!(solver.solve(&tile).risk, 40, "{}", solver.name());
+            assert_eq!(solver.solve(&cave).risk, 315, "{}", solver.name());
+        }
+        // zero risks make the A* estimate 0, which still finds the cheapest path
+        let flat = read_input("1000\n9990\n0000").unwrap();
+        for solver in Solver::ALL {
+            assert_eq!(solver.solve(&flat).risk, 0, "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn test_route_with() {
+        let tile = read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap();
+        let cave = expand_cave(&tile);
+        let puzzle = Pathfinder::new(Enter);
+        assert_eq!(find_route_with(&tile, &puzzle).unwrap().risk, 40);
+        assert_eq!(find_route_with(&cave, &puzzle).unwrap().risk, 315);
+        let diagonal = Pathfinder { movement: Movement::Adjacent(Adjacency::All), ..Pathfinder::new(Enter) };
+        assert!(find_route_with(&tile, &diagonal).unwrap().risk < 40);
+        let turns = find_route_with(&tile, &Pathfinder::new(TurnPenalty(Enter, 5))).unwrap();
+        assert!(turns.risk > 40);
+        let exit = find_route_with(&tile, &Pathfinder::new(Exit)).unwrap();
+        assert_eq!(exit.risk, exit.path[..exit.path.len() - 1].iter().map(|point| *tile.at(*point) as u64).sum::<u64>());
+    }
+
+    #[test]
+    fn test_queries() {
+        let tile = read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap();
+        let routes = find_fastest_routes(&tile, 5);
+        assert_eq!(routes.len(), 5);
+        assert_eq!(routes[0].risk, 40);
+        assert!(routes.windows(2).all(|pair| pair[0].risk <= pair[1].risk));
+        let map = risk_map(&tile);
+        assert_eq!(*map.at((9, 9)), Some(40));
+        assert_eq!(*map.at((0, 0)), Some(0));
+        // from the left column to the bottom row
+        let starts: Vec<Point> = (0..10).map(|y| (0, y)).collect();
+        let goals: Vec<Point> = (0..10).map(|x| (x, 9)).collect();
+        let route = find_route_between(&tile, &starts, &goals).unwrap();
+        assert_eq!(route.risk, 0);
+        assert_eq!(route.path, vec![(0, 9)]);
+        assert_eq!(find_route_between(&tile, &[(0, 0)], &[(9, 9)]).unwrap().risk, 40);
+        let file = std::env::temp_dir().join(format!("aoc2021_risk_map_{}.csv", std::process::id()));
+        write_risk_map(&tile, &file).unwrap();
+        let csv = fs::read_to_string(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!(csv.lines().count(), 10);
+        assert!(csv.starts_with("0,1,7,"));
+        assert!(csv.trim_end().ends_with(",40"));
+    }
+
+    #[test]
+    fn test_route() {
+        let cave = read_input("1999\n1999\n1111").unwrap();
+        for solver in Solver::ALL {
+            let route = solver.solve(&cave);
+            assert_eq!(route.risk, 5, "{}", solver.name());
+            assert_eq!(route.path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 2)], "{}", solver.name());
+        }
+        let route = find_fastest_route(&cave);
+        assert_eq!(render_route(&cave, &route.path, false), "1...\n1...\n1111\n");
+        assert!(render_route(&cave, &route.path, true).starts_with("\x1b[1;31m1\x1b[0m999\n"));
+        let svg = route_to_svg(&cave, &route.path);
+        assert_eq!(svg.matches("<rect").count(), 12);
+        assert!(svg.contains("<polyline points=\"4,4 4,12 4,20 12,20 20,20 28,20\""));
+    }
+
+    #[test]
+    fn test_compare_solvers() {
+        let tile = read_input(&fs::read_to_string("./input/day_15.test.txt").unwrap()).unwrap();
+        let results = compare_solvers(&expand_cave(&tile), 2);
+        assert_eq!(results.iter().map(|(solver, _, _)| *solver).collect::<Vec<_>>(), Solver::ALL);
+        assert!(results.iter().all(|(_, risk, stats)| *risk == 315 && stats.min <= stats.max));
     }
 }
@@ -1,146 +1,304 @@
-use std::fs;
+//! Day 16: Packet Decoder. Decodes a hexadecimal transmission of nested packets, which
+//! either hold a literal value or apply an operator to their sub packets.
 
-pub fn part_1() {
-    let input = parse_to_bin("./input/day_16.txt");
-    let mut cursor_pos = 0;
-    let out = read_packet(&input, &mut cursor_pos, true);
-    println!("{:?}", out);
-    println!("{:?}", get_version_sum(out));
+use crate::{solution::Solution, error::{ParseError, ParseContext}};
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    type Input = Packet;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        decode(input)
+    }
+    fn part_1(packet: &Self::Input) -> u64 {
+        packet.version_sum()
+    }
+    fn part_2(packet: &Self::Input) -> u64 {
+        packet.eval()
+    }
 }
 
-pub fn part_2() {
-    let input = parse_to_bin("./input/day_16.txt");
-    let mut cursor_pos = 0;
-    let out = read_packet(&input, &mut cursor_pos, false);
-    println!("{:?}", out);
+/// What an operator packet does with the values of its sub packets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    /// 1 if the first value is greater than the second, else 0.
+    GreaterThan,
+    LessThan,
+    EqualTo
 }
 
-fn get_version_sum(out: Vec<i64>) -> i64 {
-    let mut next_is_version = false;
-    let mut version_sum = 0;
-    for result in out {
-        if next_is_version {
-            version_sum += result;
+impl Operator {
+    fn from_type_id(type_id: u8) -> Option<Operator> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None
         }
-        next_is_version = result == -1;
     }
-    version_sum
+
+    fn is_comparison(self) -> bool {
+        matches!(self, Operator::GreaterThan | Operator::LessThan | Operator::EqualTo)
+    }
 }
 
-fn parse_to_bin(file: &str) -> Vec<char> {
-    let input_str = fs::read_to_string(file).unwrap();
-    parse_from_string(&input_str)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Payload {
+    Literal(u64),
+    Operator(Operator, Vec<Packet>)
 }
 
-fn parse_from_string(input_str: &str) -> Vec<char> {
-    input_str
-        .chars()
-        .map(|c| c.to_digit(16).unwrap())
-        .map(|num| format!("{:04b}", num))
-        .map(|formatted| formatted.chars().collect::<Vec<char>>())
-        .flatten()
-        .collect()
+/// A decoded packet with its sub packets.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Packet {
+    pub version: u8,
+    /// 4 for literals, else the operator's type.
+    pub type_id: u8,
+    pub payload: Payload
 }
 
-fn read_packet(input: &Vec<char>, cursor_pos: &mut usize, write_bounds: bool) -> Vec<i64>  {
-    let (version, type_id) = read_headers(input, cursor_pos);
-    let mut out = vec![];
-    if write_bounds {
-        out.push(-1);
-        out.push(version.into());
+impl Packet {
+    /// Sum of the versions of this packet and all packets nested in it.
+    pub fn version_sum(&self) -> u64 {
+        let nested = match &self.payload {
+            Payload::Literal(_) => 0,
+            Payload::Operator(_, packets) => packets.iter().map(Packet::version_sum).sum()
+        };
+        self.version as u64 + nested
+    }
+
+    /// The value of the expression the packet stands for. Every sum and product on the
+    /// way has to fit into a `u64`, which `decode` makes sure of. This panics for packets
+    /// put together otherwise that don't, see [`Packet::checked_eval`].
+    pub fn eval(&self) -> u64 {
+        self.checked_eval().expect("every sum and product of the packets to fit into a u64")
     }
-    let infos = match type_id {
-        4 => read_literals(input, cursor_pos),
-        x => read_operator(input, cursor_pos, x, write_bounds)
+
+    /// Like [`Packet::eval`], but `None` if a sum or product on the way doesn't fit into
+    /// a `u64`. Decoding made sure comparisons have two and all other operators at least
+    /// one sub packet.
+    pub fn checked_eval(&self) -> Option<u64> {
+        let (operator, packets) = match &self.payload {
+            Payload::Literal(value) => return Some(*value),
+            Payload::Operator(operator, packets) => (operator, packets)
+        };
+        let values = packets.iter().map(Packet::checked_eval).collect::<Option<Vec<u64>>>()?;
+        match operator {
+            Operator::Sum => values.into_iter().try_fold(0, u64::checked_add),
+            Operator::Product => values.into_iter().try_fold(1, u64::checked_mul),
+            Operator::Minimum => values.into_iter().min(),
+            Operator::Maximum => values.into_iter().max(),
+            comparison => {
+                let (first, second) = (values[0], values[1]);
+                let holds = match comparison {
+                    Operator::GreaterThan => first > second,
+                    Operator::LessThan => first < second,
+                    _ => first == second
+                };
+                Some(holds as u64)
+            }
+        }
+    }
+}
+
+/// Decodes the outermost packet of a hexadecimal transmission. The bits after it are
+/// padding and get ignored.
+pub fn decode(input: &str) -> Result<Packet, ParseError> {
+    let ctx = ParseContext::new(Day16::DAY, input);
+    let hex = input.trim();
+    let bits = parse_hex(hex, &ctx)?;
+    let mut cursor_pos = 0;
+    read_packet(&bits, &mut cursor_pos).map_err(|err| {
+        // point at the hexadecimal digit holding the offending bit
+        let digit = (err.position / 4).min(hex.len());
+        ctx.unexpected(&hex[digit..(digit + 1).min(hex.len())], err.expected)
+    })
+}
+
+fn parse_hex(input_str: &str, ctx: &ParseContext) -> Result<Vec<char>, ParseError> {
+    if input_str.is_empty() {
+        return Err(ctx.unexpected(input_str, "a hexadecimal transmission"));
+    }
+    let nums = input_str
+        .char_indices()
+        .map(|(i, c)| c
+            .to_digit(16)
+            .ok_or_else(|| ctx.unexpected(&input_str[i..i + c.len_utf8()], "a hexadecimal digit")))
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+    Ok(nums
+        .into_iter()
+        .map(|num| format!("{:04b}", num))
+        .flat_map(|formatted| formatted.chars().collect::<Vec<char>>())
+        .collect())
+}
+
+/// A packet that can't be decoded, at the given bit of the transmission.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub position: usize,
+    pub expected: &'static str
+}
+
+/// Reads the packet starting at `cursor_pos` and moves the cursor past it.
+pub fn read_packet(input: &[char], cursor_pos: &mut usize) -> Result<Packet, DecodeError> {
+    let start = *cursor_pos;
+    let version = read_bits(input, cursor_pos, 3, "a packet version")? as u8;
+    let type_id = read_bits(input, cursor_pos, 3, "a packet type")? as u8;
+    let payload = match Operator::from_type_id(type_id) {
+        None => Payload::Literal(read_literal(input, cursor_pos)?),
+        Some(operator) => {
+            let packets = read_operator(input, cursor_pos)?;
+            let count_ok = if operator.is_comparison() { packets.len() == 2 } else { !packets.is_empty() };
+            if !count_ok {
+                let expected = if operator.is_comparison() { "exactly two sub packets to compare" } else { "at least one sub packet" };
+                return Err(DecodeError { position: start, expected });
+            }
+            Payload::Operator(operator, packets)
+        }
     };
-    out.push(infos);
-    if write_bounds {
-        out.push(-2);
+    let packet = Packet { version, type_id, payload };
+    // the sub packets were checked already, so only this packet's operator can overflow
+    if matches!(packet.payload, Payload::Operator(Operator::Sum | Operator::Product, _)) && packet.checked_eval().is_none() {
+        return Err(DecodeError { position: start, expected: "a sum or product that fits into a u64" });
     }
-    out
+    Ok(packet)
 }
 
-fn read_literals(input: &Vec<char>, cursor_pos: &mut usize) -> i64 {
-    let mut out = vec![];
+/// Reads `count` bits as a number.
+fn read_bits(input: &[char], cursor_pos: &mut usize, count: usize, expected: &'static str) -> Result<u64, DecodeError> {
+    let bits = input.get(*cursor_pos..*cursor_pos + count).ok_or(DecodeError { position: input.len(), expected })?;
+    *cursor_pos += count;
+    Ok(bits.iter().fold(0, |value, bit| value << 1 | (*bit == '1') as u64))
+}
+
+fn read_literal(input: &[char], cursor_pos: &mut usize) -> Result<u64, DecodeError> {
+    let start = *cursor_pos;
+    let mut value = 0;
     loop {
-        let signal_bit = input.get(*cursor_pos).unwrap();
-        *cursor_pos += 1;
-        let literal: String = input[*cursor_pos..*cursor_pos + 4].iter().collect();
-        out.push(literal);
-        *cursor_pos += 4;
-        if *signal_bit == '0' {
-            break;
+        let signal_bit = read_bits(input, cursor_pos, 1, "a group of a literal")?;
+        let group = read_bits(input, cursor_pos, 4, "a group of a literal")?;
+        if value >> 60 != 0 {
+            return Err(DecodeError { position: start, expected: "a literal of at most 64 bits" });
+        }
+        value = value << 4 | group;
+        if signal_bit == 0 {
+            return Ok(value);
         }
     }
-    i64::from_str_radix(&out.join(""), 2).expect(&format!("Error parsing bool from string {:?}", &out.join("")))
 }
 
-fn read_operator(input: &Vec<char>, cursor_pos: &mut usize, operator_type: u8, write_bounds: bool) -> i64 {
+fn read_operator(input: &[char], cursor_pos: &mut usize) -> Result<Vec<Packet>, DecodeError> {
     let mut out = vec![];
-    let length_type_id = input.get(*cursor_pos).unwrap();
-    *cursor_pos += 1;
-    match length_type_id {
-        '0' => {
-            let sub_packet_length: String = input[*cursor_pos..*cursor_pos + 15].iter().collect();
-            let sub_packet_length = usize::from_str_radix(&sub_packet_length, 2).unwrap();
-            *cursor_pos += 15;
-            let start_cursor = *cursor_pos;
-            while *cursor_pos < start_cursor + sub_packet_length {
-                let mut infos = read_packet(input, cursor_pos, write_bounds);
-                out.append(&mut infos);
+    match read_bits(input, cursor_pos, 1, "a length type")? {
+        0 => {
+            let sub_packet_length = read_bits(input, cursor_pos, 15, "the length of the sub packets")? as usize;
+            let end = *cursor_pos + sub_packet_length;
+            while *cursor_pos < end {
+                out.push(read_packet(input, cursor_pos)?);
+            }
+            if *cursor_pos > end {
+                return Err(DecodeError { position: end, expected: "sub packets ending at their given length" });
             }
         },
-        '1' => {
-            let num_sub_packets: String = input[*cursor_pos..*cursor_pos + 11].iter().collect();
-            let num_sub_packets = i32::from_str_radix(&num_sub_packets, 2).unwrap();
-            *cursor_pos += 11;
+        _ => {
+            let num_sub_packets = read_bits(input, cursor_pos, 11, "the number of sub packets")?;
             for _ in 0..num_sub_packets {
-                let mut infos = read_packet(input, cursor_pos, write_bounds);
-                out.append(&mut infos);
+                out.push(read_packet(input, cursor_pos)?);
             }
-        },
-        _ => panic!("There was a non-binary character in this operator!")
+        }
     };
-    let out_iter = out.iter();
-    match operator_type {
-        0 => out_iter.sum(),
-        1 => out_iter.product(),
-        2 => *out_iter.min().unwrap(),
-        3 => *out_iter.max().unwrap(),
-        5 => if out[0] > out[1] { 1 } else { 0 },
-        6 => if out[0] < out[1] { 1 } else { 0 },
-        7 => if out[0] == out[1] { 1 } else { 0 },
-        _ => panic!("Found an invalid operator type!")
-    }
-}
-
-fn read_headers(input: &Vec<char>, cursor_pos: &mut usize) -> (u8, u8) {
-    let version: String = input[*cursor_pos..*cursor_pos + 3].iter().collect();
-    let version = u8::from_str_radix(&version, 2).unwrap();
-    *cursor_pos += 3;
-    let type_id: String = input[*cursor_pos..*cursor_pos + 3].iter().collect();
-    let type_id = u8::from_str_radix(&type_id, 2).unwrap();
-    *cursor_pos += 3;
-    (version, type_id)
+    Ok(out)
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::day_16::*;
+    use crate::{day_16::*, solution};
     #[test]
     fn part_1() {
-        let input = parse_from_string("A0016C880162017C3686B18A3D4780");
-        let mut cursor_pos = 0;
-        let out = read_packet(&input, &mut cursor_pos, true);
-        
-        assert_eq!(get_version_sum(out), 31);
+        assert_eq!(decode("A0016C880162017C3686B18A3D4780").unwrap().version_sum(), 31);
+        assert_eq!(decode("8A004A801A8002F478").unwrap().version_sum(), 16);
+        assert_eq!(decode("620080001611562C8802118E34").unwrap().version_sum(), 12);
+        assert_eq!(decode("C0015000016115A2E0802F182340").unwrap().version_sum(), 23);
     }
     #[test]
     fn part_2() {
-        let input = parse_from_string("9C0141080250320F1802104A08");
-        let mut cursor_pos = 0;
-        let out = read_packet(&input, &mut cursor_pos, false);
-        assert_eq!(out[0], 1);
+        assert_eq!(decode("9C0141080250320F1802104A08").unwrap().eval(), 1);
+        let examples = [("C200B40A82", 3), ("04005AC33890", 54), ("880086C3E88112", 7), ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1), ("F600BC2D8F", 0), ("9C005AC2F8F0", 0)];
+        for (transmission, value) in examples {
+            assert_eq!(decode(transmission).unwrap().eval(), value, "{transmission}");
+        }
+    }
+    #[test]
+    fn test_packet_tree() {
+        assert_eq!(decode("D2FE28").unwrap(), Packet { version: 6, type_id: 4, payload: Payload::Literal(2021) });
+        let packet = decode("38006F45291200").unwrap();
+        assert_eq!(packet.version, 1);
+        assert_eq!(packet.type_id, 6);
+        let Payload::Operator(Operator::LessThan, packets) = &packet.payload else {
+            panic!("expected a less than operator, got {:?}", packet.payload);
+        };
+        assert_eq!(packets.iter().map(Packet::eval).collect::<Vec<_>>(), vec![10, 20]);
+        assert_eq!(packet.eval(), 1);
+        // literals use all 64 bits
+        let packet = Packet { version: 0, type_id: 0, payload: Payload::Operator(Operator::Sum, vec![
+            Packet { version: 1, type_id: 4, payload: Payload::Literal(u64::MAX) }
+        ]) };
+        assert_eq!(packet.eval(), u64::MAX);
+        assert_eq!(packet.version_sum(), 1);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_overflow() {
+        let literal = |value| Packet { version: 0, type_id: 4, payload: Payload::Literal(value) };
+        let operator = |operator, packets| Packet { version: 0, type_id: 0, payload: Payload::Operator(operator, packets) };
+        let sum = operator(Operator::Sum, vec![literal(u64::MAX), literal(1)]);
+        assert_eq!(sum.checked_eval(), None);
+        assert_eq!(operator(Operator::Product, vec![literal(1 << 32), literal(1 << 32)]).checked_eval(), None);
+        assert_eq!(operator(Operator::Product, vec![literal(1 << 32), literal(1 << 31)]).checked_eval(), Some(1 << 63));
+        // an overflow anywhere below makes the whole expression fail
+        assert_eq!(operator(Operator::LessThan, vec![sum.clone(), literal(0)]).checked_eval(), None);
+        assert_eq!(operator(Operator::Minimum, vec![literal(3), operator(Operator::Maximum, vec![literal(u64::MAX)])]).checked_eval(), Some(3));
+    }
+    #[test]
+    #[should_panic(expected = "fit into a u64")]
+    fn test_eval_overflow() {
+        let literal = Packet { version: 0, type_id: 4, payload: Payload::Literal(u64::MAX) };
+        Packet { version: 0, type_id: 0, payload: Payload::Operator(Operator::Sum, vec![literal.clone(), literal]) }.eval();
+    }
+    #[test]
+    fn test_parse_errors() {
+        let err = decode("D2FG28").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 4: expected a hexadecimal digit, found \"G\"");
+        let err = decode("D2FE").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 5: expected a group of a literal, found end of input");
+        // a less than operator with three literals
+        let err = decode("3A00C40882106").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 1: expected exactly two sub packets to compare, found \"3\"");
+        let err = decode("3A000").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 1: expected exactly two sub packets to compare, found \"3\"");
+        // the sum of u64::MAX and 1
+        let err = decode("020084FFFFFFFFFFFFFFFFFFEF102").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 1: expected a sum or product that fits into a u64, found \"0\"");
+        // the minimum of 5 and 2^32 * 2^32 points at the product
+        let err = decode("0A0084283004246108421080048C2108421000").unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 8: expected a sum or product that fits into a u64, found \"8\"");
+    }
+    #[test]
+    fn test_solve_overflow() {
+        let err = solution::solve::<Day16>("020084FFFFFFFFFFFFFFFFFFEF102", &[1, 2]).unwrap_err();
+        assert_eq!(err.to_string(), "day 16, line 1, column 1: expected a sum or product that fits into a u64, found \"0\"");
+    }
+}
@@ -1,24 +1,68 @@
+//! Day 17: Trick Shot. Finds initial velocities that make a probe, slowed down by drag
+//! and pulled down by gravity, end up in a target area.
+
 use std::collections::{HashMap, HashSet};
 
-pub fn part_1() {
-    let highest_velocity = find_velocity((150, -86), (193, -136));
-    let sum = sum_of_numbers(highest_velocity.1);
-    print!("highest point reached at {} with velocity {:?} in {} steps", sum.0, highest_velocity, sum.1);
+use crate::{solution::Solution, error::{ParseError, ParseContext}, log::{debug, warning}};
+
+/// Start (top left) and end (bottom right) of the target area, each as (x, y).
+pub type TargetArea = ((i32, i32), (i32, i32));
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    type Input = TargetArea;
+    type Part1 = i32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_target_area(input)
+    }
+    fn part_1((start, end): &Self::Input) -> i32 {
+        let highest_velocity = find_velocity(*start, *end);
+        sum_of_numbers(highest_velocity.1).0
+    }
+    fn part_2((start, end): &Self::Input) -> usize {
+        find_all_compatible_velocities(*start, *end)
+    }
 }
 
-pub fn part_2() {
-    let compatible_count = find_all_compatible_velocities((150, -86), (193, -136));
-    print!("number of velocities: {}", compatible_count);
+/// Reads a target area like `target area: x=20..30, y=-10..-5`.
+pub fn read_target_area(input: &str) -> Result<TargetArea, ParseError> {
+    let ctx = ParseContext::new(Day17::DAY, input);
+    let line = input.trim();
+    let area = line
+        .strip_prefix("target area: ")
+        .ok_or_else(|| ctx.unexpected(line, "'target area: '"))?;
+    let (x_range, y_range) = area
+        .split_once(", ")
+        .ok_or_else(|| ctx.unexpected(area, "an x and y range like 'x=20..30, y=-10..-5'"))?;
+    let read_range = |range: &str, prefix: &str| -> Result<(i32, i32), ParseError> {
+        let bounds = range
+            .strip_prefix(prefix)
+            .ok_or_else(|| ctx.unexpected(range, &format!("a range starting with '{prefix}'")))?;
+        let (min, max) = bounds
+            .split_once("..")
+            .ok_or_else(|| ctx.unexpected(bounds, "a range like '20..30'"))?;
+        Ok((ctx.number(min, "a lower bound")?, ctx.number(max, "an upper bound")?))
+    };
+    let (min_x, max_x) = read_range(x_range, "x=")?;
+    let (min_y, max_y) = read_range(y_range, "y=")?;
+    // the area starts at the top, since y points upwards
+    Ok(((min_x, max_y), (max_x, min_y)))
 }
 
-fn sum_of_numbers(num: i32) -> (i32, i32) {
+/// Sum of all numbers from 1 to `num`, i.e. the height a probe launched upwards with that
+/// velocity reaches, and the number of steps it takes.
+pub fn sum_of_numbers(num: i32) -> (i32, i32) {
     let cur = 0;
     let steps = 0;
     if num == 0 {
         return (cur, steps);
     }
     let (new_num, new_steps) = sum_of_numbers(num - 1);
-    return (num + new_num, steps + new_steps + 1);
+    (num + new_num, steps + new_steps + 1)
 }
 
 fn find_x_value_after_steps(mut velocity: i32, steps: i32) -> i32 {
@@ -34,19 +78,21 @@ fn find_x_value_after_steps(mut velocity: i32, steps: i32) -> i32 {
 }
 
 fn find_for_x_axis(min: i32, max: i32, steps: i32) -> Option<i32> {
-    let mut tried_velocity = max;
-    while tried_velocity > 0 {
+    // start with the slowest velocity, so we prefer probes that come to a halt within the area
+    let mut tried_velocity = 1;
+    while tried_velocity <= max {
         let x = find_x_value_after_steps(tried_velocity, steps);
         if x <= max && x >= min  {
             return Some(tried_velocity);
         }
-        tried_velocity -= 1;
+        tried_velocity += 1;
     }
     None
 }
 
 
-fn find_velocity(target_area_start: (i32, i32), target_area_end: (i32, i32)) -> (i32, i32) {
+/// Velocity (x, y) that reaches the target area on the highest trajectory.
+pub fn find_velocity(target_area_start: (i32, i32), target_area_end: (i32, i32)) -> (i32, i32) {
     let (min_x, min_y) = target_area_start;
     let (max_x, max_y) = target_area_end;
     let mut best_y = max_y.abs() - 1;
@@ -55,10 +101,10 @@ fn find_velocity(target_area_start: (i32, i32), target_area_end: (i32, i32)) ->
         if let Some(x) = find_for_x_axis(min_x, max_x, steps) {
             return (x, best_y);
         }
-        best_y = best_y - 1;
+        best_y -= 1;
         if best_y < min_y {
             // TODO 
-            println!("best y is not anymore in valid area!")
+            warning!("best y is not anymore in valid area!")
         }
     }
 }
@@ -76,7 +122,8 @@ fn find_velocities_for_x_axis_with_steps(min: i32, max: i32, steps: i32) -> Vec<
     result
 }
 
-fn find_all_compatible_velocities((min_x, min_y): (i32, i32), (max_x, max_y): (i32, i32)) -> usize {
+/// Number of distinct velocities that end up in the target area.
+pub fn find_all_compatible_velocities((min_x, min_y): (i32, i32), (max_x, max_y): (i32, i32)) -> usize {
     let acceleration = -1;
     let mut y_velocities_per_steps = HashMap::new();
     
@@ -86,7 +133,7 @@ fn find_all_compatible_velocities((min_x, min_y): (i32, i32), (max_x, max_y): (i
         let mut num_steps = 1;
         while cur_y >= max_y {
             if cur_y <= min_y {
-                println!("found valid y velocity {} after {} steps", v_y_start, num_steps);
+                debug!("found valid y velocity {} after {} steps", v_y_start, num_steps);
                 y_velocities_per_steps
                     .entry(num_steps)
                     .and_modify(|num: &mut Vec<i32>| num.push(v_y_start))
@@ -112,7 +159,7 @@ fn find_all_compatible_velocities((min_x, min_y): (i32, i32), (max_x, max_y): (i
                             }))
             }
     );
-    println!("total y counts: {:?}", velocities);
+    debug!("total y counts: {:?}", velocities);
     velocities.len()
     // let total_count = num_velocities_per_steps
     //     .into_iter()
@@ -125,10 +172,22 @@ fn find_all_compatible_velocities((min_x, min_y): (i32, i32), (max_x, max_y): (i
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_17::*;
     #[test]
     fn test_find_steps_between() {
 
+    }
+    #[test]
+    fn test_read_target_area() {
+        assert_eq!(read_target_area(&fs::read_to_string("./input/day_17.test.txt").unwrap()), Ok(((20, -5), (30, -10))));
+    }
+    #[test]
+    fn test_parse_errors() {
+        let err = read_target_area("target area: x=20..30, y=-10..").unwrap_err();
+        assert_eq!(err.to_string(), "day 17, line 1, column 31: expected an upper bound, found end of input");
+        let err = read_target_area("target area: x=20..30").unwrap_err();
+        assert_eq!(err.position(), (1, 14));
     }
     #[test]
     fn test_find_for_x_axis() {
@@ -1,40 +1,63 @@
-use std::{fs};
-use self::number_entry::*;
+//! Day 18: Snailfish. Adds up snailfish numbers, pairs of numbers or pairs nested up to
+//! four levels deep, and computes the magnitude of the result.
+//!
+//! A snailfish number is stored as a flat list of `NumberEntry`s which reference each other by id.
+
+use crate::{solution::Solution, error::{ParseError, ParseContext}};
+pub use self::number_entry::*;
 mod number_entry;
 
-pub fn part_1() {
-    let mut ops = create_fishnumbers_from_path("./input/day_18.txt");
-    let mut first_num = ops.remove(0);
-    print!("  initial num: ");
-    print(find_root(&first_num), &first_num);
-    while ops.len() > 0 {
-        let first_op = ops.remove(0);
-        add(&mut first_num, first_op);
-        println!("")
-    }
-    println!("{}", magnitude(find_root(&first_num), &first_num));
-}
+pub struct Day18;
 
-pub fn part_2() {
-    let ops = create_fishnumbers_from_path("./input/day_18.txt");
-    let max_magnitude = find_max_magnitude_in_permutations(ops);
-    println!("Maximum magnitude: {}", max_magnitude);
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    type Input = Vec<Vec<NumberEntry>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        create_fishnumbers(input)
+    }
+    fn part_1(input: &Self::Input) -> usize {
+        let mut ops = input.iter().cloned();
+        let mut first_num = ops.next().unwrap();
+        for op in ops {
+            add(&mut first_num, op);
+        }
+        magnitude(find_root(&first_num), &first_num)
+    }
+    fn part_2(input: &Self::Input) -> usize {
+        find_max_magnitude_in_permutations(input)
+    }
 }
 
-fn create_fishnumbers_from_path(path: &str) -> Vec<Vec<NumberEntry>> {
-    fs::read_to_string(path)
-        .unwrap()
+/// Reads one snailfish number like `[[1,2],3]` per line.
+pub fn create_fishnumbers(input: &str) -> Result<Vec<Vec<NumberEntry>>, ParseError> {
+    let ctx = ParseContext::new(Day18::DAY, input);
+    if input.trim().is_empty() {
+        return Err(ctx.unexpected(input.trim(), "a snailfish number"));
+    }
+    input
         .trim()
-        .split("\n")
-        .map(|str| str.chars().collect())
-        .map(|char_vec| {
+        .lines()
+        .map(|line| {
             let mut cursor = 0;
             let mut lookup = vec![];
-            create_pair(0, &mut cursor, &char_vec, &mut lookup, None);
-            lookup
+            create_pair(0, &mut cursor, line, &ctx, &mut lookup, None)?;
+            if cursor + 1 < line.len() {
+                return Err(ctx.unexpected(char_at(line, cursor + 1), "the end of the line"));
+            }
+            Ok(lookup)
         }).collect()
 }
 
+/// The character at byte `index` of `line`, or the empty slice at its end.
+fn char_at(line: &str, index: usize) -> &str {
+    line.get(index..)
+        .and_then(|rest| rest.chars().next().map(|c| &rest[..c.len_utf8()]))
+        .unwrap_or(&line[line.len()..])
+}
+
 fn create_literal(depth: usize, value: usize, lookup: &mut Vec<NumberEntry>, parent_id: usize) -> usize {
     let id = lookup.len();
     let entry = NumberEntry::Literal(NumberInfo::new(id, value, depth, Some(parent_id)));
@@ -42,33 +65,43 @@ fn create_literal(depth: usize, value: usize, lookup: &mut Vec<NumberEntry>, par
     id
 }
 
-fn create_pair(depth: usize, cursor: &mut usize, cur_operation: &Vec<char>, lookup: &mut Vec<NumberEntry>, parent: Option<usize>) -> usize {
+fn create_element(depth: usize, cursor: &mut usize, line: &str, ctx: &ParseContext, lookup: &mut Vec<NumberEntry>, parent_id: usize) -> Result<usize, ParseError> {
+    let c = char_at(line, *cursor);
+    match c.chars().next() {
+        Some('[') => create_pair(depth, cursor, line, ctx, lookup, Some(parent_id)),
+        Some(digit @ '0'..='9') => Ok(create_literal(depth, digit.to_digit(10).unwrap() as usize, lookup, parent_id)),
+        _ => Err(ctx.unexpected(c, "'[' or a digit"))
+    }
+}
+
+fn expect_char(expected: char, cursor: usize, line: &str, ctx: &ParseContext) -> Result<(), ParseError> {
+    let c = char_at(line, cursor);
+    if !c.starts_with(expected) {
+        return Err(ctx.unexpected(c, &format!("'{expected}'")));
+    }
+    Ok(())
+}
+
+fn create_pair(depth: usize, cursor: &mut usize, line: &str, ctx: &ParseContext, lookup: &mut Vec<NumberEntry>, parent: Option<usize>) -> Result<usize, ParseError> {
+    expect_char('[', *cursor, line, ctx)?;
     let pair_id = lookup.len();
     lookup.push(NumberEntry::None);
     *cursor += 1;
-    let c = cur_operation.get(*cursor).unwrap();
-    let left = match c {
-        '[' => create_pair(depth + 1, cursor, cur_operation, lookup, Some(pair_id)),
-        '0'..='9' => create_literal(depth + 1, c.to_digit(10).unwrap() as usize, lookup, pair_id),
-        x => panic!("We got something other than '[' or 0-9 after a '[' at index {}: {:?}", *cursor, x)
-    };
+    let left = create_element(depth + 1, cursor, line, ctx, lookup, pair_id)?;
     // we must be at ] or number now, so skip this and a ,
+    expect_char(',', *cursor + 1, line, ctx)?;
     *cursor += 2;
-    let c = cur_operation.get(*cursor).unwrap();
-    let right = match c {
-        '[' => create_pair(depth + 1, cursor, cur_operation, lookup, Some(pair_id)),
-        '0'..='9' => create_literal(depth + 1, c.to_digit(10).unwrap() as usize, lookup, pair_id),
-        x => panic!("We got something other than '[' or 0-9 after a ',' at index {}: {:?}", *cursor, x)
-    };
+    let right = create_element(depth + 1, cursor, line, ctx, lookup, pair_id)?;
     // we must be at a ']' now
     *cursor += 1;
+    expect_char(']', *cursor, line, ctx)?;
     
     let new_entry = NumberEntry::Pair(PairInfo::new(pair_id, left, right, depth, parent));
     lookup[pair_id] = new_entry;
-    pair_id
+    Ok(pair_id)
 }
 
-fn increment_depth(entry: usize, lookup: &mut Vec<NumberEntry>) {
+fn increment_depth(entry: usize, lookup: &mut [NumberEntry]) {
     let entry = lookup.get_mut(entry).unwrap();
     match entry {
         NumberEntry::Literal(l) => l.depth += 1,
@@ -253,7 +286,7 @@ fn reindex(offset: usize, lookup_left: &mut Vec<NumberEntry>, lookup_right: Vec<
     });
 }
 
-fn check_explosions(lookup: &Vec<NumberEntry>) -> Option<usize> {
+fn check_explosions(lookup: &[NumberEntry]) -> Option<usize> {
     lookup
         .iter()
         .filter_map(|num| {
@@ -269,7 +302,7 @@ fn check_explosions(lookup: &Vec<NumberEntry>) -> Option<usize> {
         .map(|(id, _)| id)
 }
 
-fn check_splits(lookup: &Vec<NumberEntry>) -> Option<usize> {
+fn check_splits(lookup: &[NumberEntry]) -> Option<usize> {
     lookup
         .iter()
         .filter_map(|num| {
@@ -285,7 +318,7 @@ fn check_splits(lookup: &Vec<NumberEntry>) -> Option<usize> {
         .map(|(id, _)| id)
 }
 
-fn calc_left_score(id: usize, lookup: &Vec<NumberEntry>) -> usize {
+fn calc_left_score(id: usize, lookup: &[NumberEntry]) -> usize {
     let num = lookup.get(id).unwrap();
     let parent = num.get_parent();
     let depth = num.get_depth();
@@ -300,11 +333,13 @@ fn calc_left_score(id: usize, lookup: &Vec<NumberEntry>) -> usize {
     score
 }
 
-fn add(lookup_left: &mut Vec<NumberEntry>, lookup_right: Vec<NumberEntry>) -> usize {
+/// Adds the right number to the left one and reduces the result in place.
+/// Returns the id of the new root pair.
+pub fn add(lookup_left: &mut Vec<NumberEntry>, lookup_right: Vec<NumberEntry>) -> usize {
     let offset = lookup_left.len();
     reindex(offset, lookup_left, lookup_right);
     let new_id = lookup_left.len();
-    let left_root = find_root(&lookup_left);
+    let left_root = find_root(lookup_left);
     update_parent(lookup_left.get_mut(left_root).unwrap(), Some(new_id));
     update_parent(lookup_left.get_mut(offset).unwrap(), Some(new_id));
     lookup_left.push(NumberEntry::Pair(PairInfo::new(new_id, left_root, offset, 0, None)));
@@ -326,10 +361,11 @@ fn add(lookup_left: &mut Vec<NumberEntry>, lookup_right: Vec<NumberEntry>) -> us
     new_id
 }
 
-fn find_root(num: &Vec<NumberEntry>) -> usize {
+/// Id of the outermost pair of a number.
+pub fn find_root(num: &[NumberEntry]) -> usize {
     num.iter().find_map(|num| {
         if let NumberEntry::Pair(p) = num {
-            if p.parent == None {
+            if p.parent.is_none() {
                 return Some(p.id);
             }
         };
@@ -337,7 +373,8 @@ fn find_root(num: &Vec<NumberEntry>) -> usize {
     }).unwrap()
 }
 
-fn magnitude(num_id: usize, lookup: &Vec<NumberEntry>) -> usize {
+/// Magnitude of the number starting at `num_id`: 3 times its left plus 2 times its right element.
+pub fn magnitude(num_id: usize, lookup: &[NumberEntry]) -> usize {
     let num = lookup.get(num_id).unwrap();
     match num {
         NumberEntry::Literal(l) => l.value,
@@ -349,7 +386,8 @@ fn magnitude(num_id: usize, lookup: &Vec<NumberEntry>) -> usize {
     }
 }
 
-fn print(entry: usize, lookup: &Vec<NumberEntry>) {
+#[cfg(test)]
+fn print(entry: usize, lookup: &[NumberEntry]) {
     let entry = lookup.get(entry).unwrap();
     match entry {
         NumberEntry::Literal(l) => {
@@ -372,7 +410,8 @@ fn print(entry: usize, lookup: &Vec<NumberEntry>) {
     }
 }
 
-fn find_max_magnitude_in_permutations(ops: Vec<Vec<NumberEntry>>) -> usize {
+/// Largest magnitude of adding any two different numbers.
+pub fn find_max_magnitude_in_permutations(ops: &[Vec<NumberEntry>]) -> usize {
     let mut max_magnitude = 0;
     for num_a in ops.iter() {
         for num_b in ops.iter() {
@@ -389,34 +428,35 @@ fn find_max_magnitude_in_permutations(ops: Vec<Vec<NumberEntry>>) -> usize {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_18::*;
     #[test]
     fn test_part_1() {
-        let mut ops = create_fishnumbers_from_path("./input/day_18.test.txt");
+        let mut ops = create_fishnumbers(&fs::read_to_string("./input/day_18.test.txt").unwrap()).unwrap();
         let mut first_num = ops.remove(0);
         print!("  initial num: ");
         print(find_root(&first_num), &first_num);
         let mut root = 0;
-        while ops.len() > 0 {
+        while !ops.is_empty() {
             let first_op = ops.remove(0);
             root = add(&mut first_num, first_op);
-            println!("")
+            println!()
         }
         assert_eq!(magnitude(root, &first_num), 4140);
     }
 
     #[test]
     fn test_part_2() {
-        let ops = create_fishnumbers_from_path("./input/day_18.test.txt");
-        assert_eq!(find_max_magnitude_in_permutations(ops), 3993);
+        let ops = create_fishnumbers(&fs::read_to_string("./input/day_18.test.txt").unwrap()).unwrap();
+        assert_eq!(find_max_magnitude_in_permutations(&ops), 3993);
     }
     #[test]
     fn test_part_1_simple() {
-        let mut ops = create_fishnumbers_from_path("./input/day_18.test.simple.txt");
+        let mut ops = create_fishnumbers(&fs::read_to_string("./input/day_18.test.simple.txt").unwrap()).unwrap();
         println!("found {} operations", ops.len());
         let mut first_num = ops.remove(0);
         print(find_root(&first_num), &first_num);
-        while ops.len() > 0 {
+        while !ops.is_empty() {
             let first_op = ops.remove(0);
             add(&mut first_num, first_op);
             let root = find_root(&first_num);
@@ -425,4 +465,15 @@ mod tests {
         }
         assert_eq!(magnitude(find_root(&first_num), &first_num), 445);
     }
+    #[test]
+    fn test_parse_errors() {
+        let err = create_fishnumbers("[1,2]\n[[1,2];3]").unwrap_err();
+        assert_eq!(err.to_string(), "day 18, line 2, column 7: expected ',', found \";\"");
+        let err = create_fishnumbers("[1,[2,x]]").unwrap_err();
+        assert_eq!(err.position(), (1, 7));
+        let err = create_fishnumbers("[1,[2,3]").unwrap_err();
+        assert!(matches!(err, ParseError::UnexpectedEnd { line: 1, column: 9, .. }));
+        let err = create_fishnumbers("\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 18, line 1, column 1: expected a snailfish number, found end of input");
+    }
 }
\ No newline at end of file
@@ -104,6 +104,7 @@ impl PairInfo {
     }
 }
 
+/// Element of a snailfish number, or `None` for a pair that is still being built.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum NumberEntry {
     Literal(NumberInfo),
@@ -1,117 +1,109 @@
-use std::{fs, collections::HashMap};
+//! Day 20: Trench Map. Repeatedly enhances an infinite image, where each pixel's new
+//! value is looked up by the 3x3 square of pixels around it.
 
-pub fn part_1() {
-    let (algo, image) = read_input("./input/day_20.txt");
-    let min = 0;
-    let max = f32::sqrt(image.len() as f32).floor() as i32 - 1;
-    let new_map = apply_algo(image, &algo, min, max, false);
-    let new_map = apply_algo(new_map, &algo, min - 1, max + 1, true);
-    println!("Result image: ");
-    print(&new_map, min - 2, max + 2);
-    println!("There are {} lights!", count_lights(&new_map));
-}
+use crate::{solution::Solution, error::{ParseError, ParseContext}, grid::Grid, log::debug};
+
+/// Whether each pixel is lit, the outside value is the infinite rest of the image.
+pub type Image = Grid<bool>;
+
+pub struct Day20;
 
-pub fn part_2() {
-    let (algo, image) = read_input("./input/day_20.txt");
-    let min = 0;
-    let max = f32::sqrt(image.len() as f32).floor() as i32 - 1;
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+    type Input = (Vec<bool>, Image);
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let mut new_map = apply_algo(image, &algo, min, max, false);
-    for i in 1..50 {
-        new_map = apply_algo(new_map, &algo, min - i, max + i, i % 2 == 1);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_input(input)
+    }
+    fn part_1((algo, image): &Self::Input) -> usize {
+        count_lights(&enhance(image.clone(), algo, 2))
+    }
+    fn part_2((algo, image): &Self::Input) -> usize {
+        count_lights(&enhance(image.clone(), algo, 50))
     }
-    println!("There are {} lights!", count_lights(&new_map));
 }
 
-fn count_lights(image: &HashMap<(i32, i32), bool>) -> usize {
+/// Applies the enhancement algorithm to the image a number of times.
+pub fn enhance(image: Image, algo: &[bool], times: i32) -> Image {
+    (0..times).fold(image, |image, _| apply_algo(image, algo))
+}
+
+pub fn count_lights(image: &Image) -> usize {
     image
         .iter()
         .filter(|(_, light)| **light)
         .count()
 }
 
-fn apply_algo(mut lookup: HashMap<(i32, i32), bool>, algo: &Vec<bool>, min: i32, max: i32, default: bool) -> HashMap<(i32, i32), bool> {
-    let mut result = HashMap::new();
-    // lookup for max +1 and +2 is always default
-    for i in 1..3 {
-        for x in (min - 2)..=(max + 2) {
-            lookup.insert((x, min - i), default);
-            lookup.insert((x, max + i), default);
-        }
-        for y in (min - 2)..=(max + 2) {
-            lookup.insert((min - i, y), default);
-            lookup.insert((max + i, y), default);
-        }
-    }
-    println!("input image:");
-    print(&lookup, min - 2, max + 2);
-    for x in (min - 1)..=(max + 1) {
-        for y in (min - 1)..=(max + 1) {
-            let coord_sum = calc_coord_sum((x, y), &lookup);
-            result.insert((x, y), *algo.get(coord_sum as usize).unwrap());
-        }
+/// Enhances the image once, it grows by a pixel on every side.
+fn apply_algo(mut image: Image, algo: &[bool]) -> Image {
+    debug!("input image:\n{}", render(&image));
+    image.expand(1);
+    let mut result = image.clone();
+    for point in image.points() {
+        result.set(point, algo[calc_coord_sum(point, &image) as usize]);
     }
-    return result;
+    // if an empty area lights up, the infinite surroundings flip on every step
+    result.set_outside(algo[if *image.outside() { 511 } else { 0 }]);
+    result
 }
 
-fn calc_coord_sum((x, y): (i32, i32), lookup: &HashMap<(i32, i32), bool>) -> u32 {
-    let lookup_coords = vec![
+/// Index into the algorithm for the pixel at (x, y), read from the 3x3 square around it.
+fn calc_coord_sum((x, y): (i32, i32), image: &Image) -> u32 {
+    let lookup_coords = [
         (-1, -1), (0, -1), (1, -1),
         (-1, 0), (0, 0), (1, 0),
         (-1, 1), (0, 1), (1, 1)
     ];
-    let res_string: String = lookup_coords
-    .iter()
-    // we have to calculate what our outer number would be if it's not available yet
-    .map(|lookup_coord| {
-        let final_coords = (lookup_coord.0 + x, lookup_coord.1 + y);
-        if let Some(light) = lookup.get(&final_coords) {
-            *light
-        } else {
-            panic!("We don't have an entry for {:?}!", final_coords);
-        }
-    })
-    .map(|b| if b { "1" } else { "0" })
-    .collect();
-    u32::from_str_radix(&res_string, 2).unwrap()
+    lookup_coords
+        .iter()
+        .fold(0, |sum, (dx, dy)| sum << 1 | *image.at((x + dx, y + dy)) as u32)
 }
 
-fn read_input(path: &str) -> (Vec<bool>, HashMap<(i32, i32), bool>) {
-    let data = fs::read_to_string(path).unwrap();
-    let (algo, image) = data
-        .split("\n\n")
-        .fold((None, None), |(algo, _), line| {
-            if let Some(algo) = algo {
-                let image: Vec<Vec<bool>> = line.split("\n").map(|image_line| image_line.chars().map(|c| c == '#').collect()).collect();
-                (Some(algo), Some(image))
-            } else {
-                (Some(line.chars().map(|c| c == '#').collect()), None)
-            }
-        });
-    let map = image.unwrap().iter().enumerate().fold(HashMap::new(), |mut map, (y, line)| {
-        map.extend(line.iter().enumerate().map(|(x, light)|((x as i32, y as i32), *light)));
-        map
-    });
-    (algo.unwrap(), map)
+/// Reads the 512 pixel enhancement algorithm and the input image.
+pub fn read_input(data: &str) -> Result<(Vec<bool>, Image), ParseError> {
+    let ctx = ParseContext::new(Day20::DAY, data);
+    let (algo_str, image_str) = data
+        .trim()
+        .split_once("\n\n")
+        .ok_or_else(|| ctx.missing_after(data.trim_end(), "an empty line followed by the image"))?;
+    let algo = read_pixels(algo_str, &ctx)?;
+    if algo.len() != 512 {
+        return Err(ctx.unexpected(algo_str, "an enhancement algorithm of 512 pixels"));
+    }
+    let image = Grid::parse(image_str, &ctx, "'#' or '.'", pixel)?;
+    Ok((algo, image))
 }
 
-fn print(map: &HashMap<(i32, i32), bool>, min: i32, max: i32) {
-    for y in min..=max {
-        for x in min..=max {
-            let coords = *map.get(&(x, y)).expect(&format!("Didn't find entry for ({},{})!", x, y));
-
-            print!("{}", if coords { "#" } else { "." });
-        }
-        println!("");
+fn pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None
     }
 }
 
+fn read_pixels(line: &str, ctx: &ParseContext) -> Result<Vec<bool>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| pixel(c).ok_or_else(|| ctx.unexpected(&line[i..i + c.len_utf8()], "'#' or '.'")))
+        .collect()
+}
+
+/// Draws the image with a border of two pixels of its infinite surroundings.
+fn render(image: &Image) -> String {
+    let (min, max) = (image.min(), image.max());
+    image.render_area((min.0 - 2, min.1 - 2), (max.0 + 2, max.1 + 2), |light| if *light { '#' } else { '.' })
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_20::*;
     #[test]
     fn test_part_1() {
-        let (algo, coords) = read_input("./input/day_20.test.txt");
+        let (algo, coords) = read_input(&fs::read_to_string("./input/day_20.test.txt").unwrap()).unwrap();
         assert_eq!(algo.len(), 512);
         let lookup_coords =
         [
@@ -120,23 +112,27 @@ mod tests {
             ((0, 2), true), ((1, 2), true), ((2, 2), false), ((3, 2), false), ((4, 2), true), 
             ((0, 3), false), ((1, 3), false), ((2, 3), true), ((3, 3), false), ((4, 3), false), 
             ((0, 4), false), ((1, 4), false), ((2, 4), true), ((3, 4), true), ((4, 4), true), 
-         ]
-         .into_iter().collect();
-        assert_eq!(coords, lookup_coords);
-        let min = 0;
-        let max = f32::sqrt(coords.len() as f32).floor() as i32 - 1;
+         ];
+        assert_eq!((coords.width(), coords.height()), (5, 5));
+        assert!(lookup_coords.iter().all(|(point, light)| coords.at(*point) == light));
         assert_eq!(calc_coord_sum((2, 2), &coords), 34);
         
-        print(&coords, min, max);
-        let new_map = apply_algo(coords, &algo, min, max, false);
+        print!("{}", render(&coords));
+        let new_map = apply_algo(coords, &algo);
         assert_eq!(count_lights(&new_map), 24);
-        let mut new_map = apply_algo(new_map, &algo, min - 1, max + 1, false);
+        let mut new_map = apply_algo(new_map, &algo);
         println!("result image:");
-        print(&new_map, min - 2, max + 2);
+        print!("{}", render(&new_map));
         assert_eq!(count_lights(&new_map), 35);
-        for i in 2..50 {
-            new_map = apply_algo(new_map, &algo, min - i, max + i, false);
+        for _ in 2..50 {
+            new_map = apply_algo(new_map, &algo);
         }
         assert_eq!(count_lights(&new_map), 3351);
     }
+    #[test]
+    fn test_enhance() {
+        let (algo, coords) = read_input(&fs::read_to_string("./input/day_20.test.txt").unwrap()).unwrap();
+        assert_eq!(count_lights(&enhance(coords.clone(), &algo, 2)), 35);
+        assert_eq!(count_lights(&enhance(coords, &algo, 50)), 3351);
+    }
 }
\ No newline at end of file
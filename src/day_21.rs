@@ -1,16 +1,51 @@
-pub fn part_1() {
-    let (loser_score, die_rolls) = play_game_deterministic(6, 2);
-    println!("Final result: {}", loser_score * die_rolls);
+//! Day 21: Dirac Dice. Plays a board game first with a deterministic die, then with a
+//! quantum die that splits the universe on every roll.
+
+use crate::{solution::Solution, error::{ParseError, ParseContext}};
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+    type Input = (u8, u8);
+    type Part1 = u32;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        read_start_positions(input)
+    }
+    fn part_1((p1_start, p2_start): &Self::Input) -> u32 {
+        let (loser_score, die_rolls) = play_game_deterministic(*p1_start as u32, *p2_start as u32);
+        loser_score * die_rolls
+    }
+    fn part_2((p1_start, p2_start): &Self::Input) -> u64 {
+        play_game_random(*p1_start, *p2_start)
+    }
 }
 
-pub fn part_2() {
-    let score = play_game_random(6, 2);
-    println!("The winner wins in {} universes!", score);
+/// Reads the starting positions of both players.
+pub fn read_start_positions(input: &str) -> Result<(u8, u8), ParseError> {
+    let ctx = ParseContext::new(Day21::DAY, input);
+    let mut positions = input
+        .lines()
+        .map(|line| {
+            let (_, position) = line
+                .split_once("starting position: ")
+                .ok_or_else(|| ctx.unexpected(line, "a line like 'Player 1 starting position: 4'"))?;
+            match ctx.number::<u8>(position, "a position from 1 to 10")? {
+                position @ 1..=10 => Ok(position),
+                _ => Err(ctx.unexpected(position, "a position from 1 to 10"))
+            }
+        });
+    let p1 = positions.next().unwrap_or_else(|| Err(ctx.unexpected(&input[input.len()..], "the start of player 1")))?;
+    let p2 = positions.next().unwrap_or_else(|| Err(ctx.unexpected(&input[input.len()..], "the start of player 2")))?;
+    Ok((p1, p2))
 }
 
 struct Player(u8, u8);
 
-fn play_game_random(p1_start: u8, p2_start: u8) -> u64 {
+/// Number of universes the player who wins in more universes wins in, playing with the quantum die.
+pub fn play_game_random(p1_start: u8, p2_start: u8) -> u64 {
     // in 1 turn a player can move 3-9 fields. possibilities per move:
     // 3: 1 (1-1-1)
     // 4: 3 (2-1-1, 1-2-1, 1-1-2)
@@ -28,14 +63,14 @@ fn play_game_random(p1_start: u8, p2_start: u8) -> u64 {
 fn check_all_winners_after_turn(p1: &Player, p2: &Player, turn: usize, move_positions: [u8; 7]) -> (u64, u64) {
     let mut p1_wins = 0;
     let mut p2_wins = 0;
-    let player = if turn % 2 == 0 { p1 } else { p2 };
+    let player = if turn.is_multiple_of(2) { p1 } else { p2 };
 
     for (move_index, count) in move_positions.iter().enumerate() {
         let move_amount = move_index as u8 + 3;
         let result_position = (player.0 + move_amount) % 10;
         let result_position = if result_position == 0 { 10 } else { result_position };
         let result_score = player.1 + result_position;
-        let (p1_add, p2_add) = match (result_score >= 21, turn % 2 == 0) {
+        let (p1_add, p2_add) = match (result_score >= 21, turn.is_multiple_of(2)) {
             (true, true) => (1, 0),
             (true, false) => (0, 1),
             (false, true) => check_all_winners_after_turn(&Player(result_position, result_score), p2, turn + 1, move_positions),
@@ -47,7 +82,9 @@ fn check_all_winners_after_turn(p1: &Player, p2: &Player, turn: usize, move_posi
     (p1_wins, p2_wins)
 }
 
-fn play_game_deterministic(p1_start: u32, p2_start: u32) -> (u32, u32) {
+/// Plays with the deterministic die until someone reaches 1000 points.
+/// Returns the score of the losing player and the number of rolls.
+pub fn play_game_deterministic(p1_start: u32, p2_start: u32) -> (u32, u32) {
     let mut p1_score = 0;
     let mut p1_location = p1_start;
     let mut p2_score = 0;
@@ -83,6 +120,7 @@ fn roll_die_deterministic(board_offset: u32, die_offset: u32) -> u32 {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_21::*;
     #[test]
     fn test_part_1() {
@@ -92,6 +130,17 @@ mod tests {
         assert_eq!(loser_score * die_rolls, 739785);
     }
     #[test]
+    fn test_read_start_positions() {
+        assert_eq!(read_start_positions(&fs::read_to_string("./input/day_21.test.txt").unwrap()), Ok((4, 8)));
+    }
+    #[test]
+    fn test_parse_errors() {
+        let err = read_start_positions("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err();
+        assert_eq!(err.to_string(), "day 21, line 2, column 29: expected a position from 1 to 10, found \"11\"");
+        let err = read_start_positions("Player 1 starting position: 4\n").unwrap_err();
+        assert_eq!(err.position(), (2, 1));
+    }
+    #[test]
     fn test_part_2() {
         assert_eq!(444356092776315, play_game_random(4, 8));
     }
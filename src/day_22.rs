@@ -1,70 +1,86 @@
-use std::{fs, collections::HashMap};
-use self::cube::*;
+//! Day 22: Reactor Reboot. Turns cuboids of cubes in a reactor on and off and counts
+//! the cubes that end up on.
+
+use std::collections::HashMap;
+use crate::{solution::Solution, error::{ParseError, ParseContext}, vec3::Vec3};
+pub use self::cube::*;
 mod cube;
 
-pub fn part_1() {
-    let instructions = parse_instructions("./input/day_22.txt");
-    let mut cubes = HashMap::new();
-    for instruction in instructions {
-        apply_instruction_clamped(&mut cubes, instruction, 50);
-    }
-    let on_cubes = cubes
-        .into_iter()
-        .filter(|(_, is_on)| *is_on)
-        .count();
-    println!("Enabled cubes after instructions: {}", on_cubes);
-}
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+    type Input = Vec<(bool, Vec3, Vec3)>;
+    type Part1 = usize;
+    type Part2 = i64;
 
-pub fn part_2() {
-    let instructions = parse_instructions("./input/day_22.txt");
-    let cubes = instructions.into_iter().fold(vec![], apply_instruction);
-    let lights = count_lights(cubes);
-    println!("{} lights are lit!", lights);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_instructions(input)
+    }
+    fn part_1(input: &Self::Input) -> usize {
+        let mut cubes = HashMap::new();
+        for instruction in input {
+            apply_instruction_clamped(&mut cubes, *instruction, 50);
+        }
+        cubes
+            .into_iter()
+            .filter(|(_, is_on)| *is_on)
+            .count()
+    }
+    fn part_2(input: &Self::Input) -> i64 {
+        let cubes = input.iter().copied().fold(vec![], apply_instruction);
+        count_lights(cubes)
+    }
 }
 
 
-fn parse_instructions(path: &str) -> Vec<(bool, Coord, Coord)> {
-    fs::read_to_string(path)
-        .unwrap()
+/// Reads the reboot steps, each as whether it turns cubes on and the two corners of its cuboid.
+pub fn parse_instructions(input: &str) -> Result<Vec<(bool, Vec3, Vec3)>, ParseError> {
+    let ctx = ParseContext::new(Day22::DAY, input);
+    input
         .trim()
-        .split('\n')
+        .lines()
         .map(|line| {
-            line
-                .split(' ')
-                .fold(None, |acc, val| {
-                    if let Some((state, _, _)) = acc {
-                        let coords: Vec<(i32, i32)> = val
-                            .split(',')
-                            .map(|val| {
-                                let mut coords_iter = val[2..]
-                                    .split("..")
-                                    .map(|coord| i32::from_str_radix(coord, 10).expect(&format!("Error parsing {} as number, within {}!", coord, val)));
-                                (coords_iter.next().unwrap(), coords_iter.next().unwrap())
-                            }).collect();
-                        Some((state,
-                            Coord(coords[0].0, coords[1].0, coords[2].0),
-                            Coord(coords[0].1, coords[1].1, coords[2].1)))
-                    } else {
-                        Some((val == "on", Coord(0, 0, 0), Coord(0, 0, 0)))
-                    }
-                }).unwrap()
+            let (state, cuboid) = line
+                .split_once(' ')
+                .ok_or_else(|| ctx.unexpected(line, "a step like 'on x=10..12,y=10..12,z=10..12'"))?;
+            let state = match state {
+                "on" => true,
+                "off" => false,
+                _ => return Err(ctx.unexpected(state, "'on' or 'off'"))
+            };
+            let mut ranges = cuboid.split(',');
+            let mut read_range = |axis: &str| -> Result<(i32, i32), ParseError> {
+                let range = ctx.next(&mut ranges, cuboid, &format!("a range for {axis}"))?;
+                let bounds = range
+                    .strip_prefix(axis)
+                    .and_then(|range| range.strip_prefix('='))
+                    .ok_or_else(|| ctx.unexpected(range, &format!("a range like '{axis}=10..12'")))?;
+                let (from, to) = bounds
+                    .split_once("..")
+                    .ok_or_else(|| ctx.unexpected(bounds, "a range like '10..12'"))?;
+                Ok((ctx.number(from, "a lower bound")?, ctx.number(to, "an upper bound")?))
+            };
+            let (x, y, z) = (read_range("x")?, read_range("y")?, read_range("z")?);
+            Ok((state, Vec3::new(x.0, y.0, z.0), Vec3::new(x.1, y.1, z.1)))
         })
         .collect()
 }
 
-fn apply_instruction_clamped(cubes: &mut HashMap<Coord, bool>, (on, from, to): (bool, Coord, Coord), clamp: i32) {
-    if (from.0 < -clamp && to.0 < -clamp)
-        || (from.0 > clamp && to.0 > clamp)
-        || (from.1 < -clamp && to.1 < -clamp)
-        || (from.1 > clamp && to.1 > clamp)
-        || (from.2 < -clamp && to.2 < -clamp)
-        || (from.2 > clamp && to.2 > clamp) {
+/// Applies a step cube by cube, ignoring steps completely outside of -clamp..=clamp.
+pub fn apply_instruction_clamped(cubes: &mut HashMap<Vec3, bool>, (on, from, to): (bool, Vec3, Vec3), clamp: i32) {
+    if (from.x < -clamp && to.x < -clamp)
+        || (from.x > clamp && to.x > clamp)
+        || (from.y < -clamp && to.y < -clamp)
+        || (from.y > clamp && to.y > clamp)
+        || (from.z < -clamp && to.z < -clamp)
+        || (from.z > clamp && to.z > clamp) {
         return;
     }
-    for x in from.0.clamp(-clamp, clamp)..=to.0.clamp(-clamp, clamp) {
-        for y in from.1.clamp(-clamp, clamp)..=to.1.clamp(-clamp, clamp) {
-            for z in from.2.clamp(-clamp, clamp)..=to.2.clamp(-clamp, clamp) {
-                cubes.insert(Coord(x, y, z), on);
+    for x in from.x.clamp(-clamp, clamp)..=to.x.clamp(-clamp, clamp) {
+        for y in from.y.clamp(-clamp, clamp)..=to.y.clamp(-clamp, clamp) {
+            for z in from.z.clamp(-clamp, clamp)..=to.z.clamp(-clamp, clamp) {
+                cubes.insert(Vec3::new(x, y, z), on);
             }
         }
     }
@@ -81,25 +97,25 @@ fn update_negative_intersections(c: Cube, int: Vec<(Cube, bool)>) -> Vec<(Cube,
     res_ints
 }
 
-fn calc_inverted_intersections(c: Cube, int: &Vec<(Cube, bool)>) -> Vec<(Cube, bool)> {
+fn calc_inverted_intersections(c: Cube, int: &[(Cube, bool)]) -> Vec<(Cube, bool)> {
     let mut res_ops = vec![(c, false)];
     res_ops.extend(int
-        .into_iter()
+        .iter()
         .filter_map(|(int, add)| int
-            .calc_intersection_cube(&c)
-            .and_then(|c| Some((c, !add)))));
+            .calc_intersection_cube(&c).map(|c| (c, !add))));
     res_ops
 }
 
-fn apply_instruction(cubes: Vec<(Cube, Vec<(Cube, bool)>)>, (on, from, to): (bool, Coord, Coord)) -> Vec<(Cube, Vec<(Cube, bool)>)> {
+/// Applies a step to a list of cuboids that were turned on, each with the intersections
+/// (added or removed) that correct its volume for the cuboids overlapping it.
+pub fn apply_instruction(cubes: Vec<(Cube, Vec<(Cube, bool)>)>, (on, from, to): (bool, Vec3, Vec3)) -> Vec<(Cube, Vec<(Cube, bool)>)> {
     let new_cube = Cube(from, to);
     if on {
         let mut res_cubes = vec![];
         let intersections: Vec<(Cube, bool)> = cubes
         .iter()
         .filter_map(|(cube, int)| cube
-            .calc_intersection_cube(&new_cube)
-            .and_then(|c| Some(calc_inverted_intersections(c, int)))
+            .calc_intersection_cube(&new_cube).map(|c| calc_inverted_intersections(c, int))
         )
         .flatten()
         .collect();
@@ -120,7 +136,8 @@ fn apply_instruction(cubes: Vec<(Cube, Vec<(Cube, bool)>)>, (on, from, to): (boo
     }
 }
 
-fn count_lights(operations: Vec<(Cube, Vec<(Cube, bool)>)>) -> i64 {
+/// Number of cubes that are on after applying all steps with `apply_instruction`.
+pub fn count_lights(operations: Vec<(Cube, Vec<(Cube, bool)>)>) -> i64 {
     operations
         .into_iter()
         .map(|(cube, operations)| operations
@@ -132,10 +149,11 @@ fn count_lights(operations: Vec<(Cube, Vec<(Cube, bool)>)>) -> i64 {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use crate::day_22::*;
     #[test]
     fn test_part_1() {
-        let instructions = parse_instructions("./input/day_22.test.txt");
+        let instructions = parse_instructions(&fs::read_to_string("./input/day_22.test.txt").unwrap()).unwrap();
         let mut cubes = HashMap::new();
         for instruction in instructions {
             apply_instruction_clamped(&mut cubes, instruction, 50);
@@ -148,7 +166,7 @@ mod tests {
     }
     #[test]
     fn test_part_2() {
-        let instructions = parse_instructions("./input/day_22.test.2.txt");
+        let instructions = parse_instructions(&fs::read_to_string("./input/day_22.test.2.txt").unwrap()).unwrap();
         let cubes = instructions.into_iter().fold(vec![], apply_instruction);
         let light_count = count_lights(cubes);
         assert_eq!(light_count, 2758514936282235);
@@ -156,10 +174,17 @@ mod tests {
     #[test]
     fn test_apply_instructions() {
         let instructions = vec![
-            (true, Coord(0, 0, 0), Coord(1, 1, 1)),
-            (false, Coord(0, 1, 0), Coord(2, 2, 1)),
+            (true, Vec3::new(0, 0, 0), Vec3::new(1, 1, 1)),
+            (false, Vec3::new(0, 1, 0), Vec3::new(2, 2, 1)),
         ];
         let cubes = instructions.into_iter().fold(vec![], apply_instruction);
         assert_eq!(count_lights(cubes), 4);
     }
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_instructions("on x=1..2,y=1..2,z=1..2\ntoggle x=1..2,y=1..2,z=1..2").unwrap_err();
+        assert_eq!(err.to_string(), "day 22, line 2, column 1: expected 'on' or 'off', found \"toggle\"");
+        let err = parse_instructions("off x=1..2,y=1..2").unwrap_err();
+        assert!(matches!(err, ParseError::UnexpectedEnd { line: 1, column: 18, .. }));
+    }
 }
\ No newline at end of file
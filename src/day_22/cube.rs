@@ -1,75 +1,77 @@
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub struct Coord(pub i32, pub i32, pub i32);
+use crate::vec3::Vec3;
+
+/// A cuboid spanning from its first to its second corner, both inclusive.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
-pub struct Cube(pub Coord, pub Coord);
+pub struct Cube(pub Vec3, pub Vec3);
 
 impl Cube {
 
     pub fn volume(&self) -> u64 {
-        let width: u64 = (self.1.0 - self.0.0).try_into().unwrap();
-        let height: u64 = (self.1.1 - self.0.1).try_into().unwrap();
-        let depth: u64 = (self.1.2 - self.0.2).try_into().unwrap();
+        let width: u64 = (self.1.x - self.0.x).try_into().unwrap();
+        let height: u64 = (self.1.y - self.0.y).try_into().unwrap();
+        let depth: u64 = (self.1.z - self.0.z).try_into().unwrap();
         (width + 1) * (height + 1) * (depth + 1)
     }
 
+    /// The cuboid where both overlap, if they do.
     pub fn calc_intersection_cube(&self, other: &Cube) -> Option<Cube> {
         let min_a = self.0;
         let min_b = other.0;
         let max_a = self.1;
         let max_b = other.1;
-        let x = if is_between(min_a.0, min_b.0, max_b.0) {
-            Some((min_a.0, i32::min(max_a.0, max_b.0)))
-        } else if is_between(max_a.0, min_b.0, max_b.0) {
-            Some((i32::max(min_a.0, min_b.0), max_a.0))
-        } else if is_between(min_b.0, min_a.0, max_a.0) {
-            Some((min_b.0, i32::min(max_a.0, max_b.0)))
-        } else if is_between(max_b.0, min_a.0, max_a.0) {
-            Some((i32::max(min_a.0, min_b.0), max_b.0))
-        } else if min_a.0 == min_b.0 {
-            Some((min_a.0, i32::min(max_a.0, max_b.0)))
-        } else if min_a.0 == max_b.0 {
-            Some((min_a.0, min_a.0))
-        } else if max_a.0 == min_b.0 {
-            Some((max_a.0, max_a.0))
+        let x = if is_between(min_a.x, min_b.x, max_b.x) {
+            Some((min_a.x, i32::min(max_a.x, max_b.x)))
+        } else if is_between(max_a.x, min_b.x, max_b.x) {
+            Some((i32::max(min_a.x, min_b.x), max_a.x))
+        } else if is_between(min_b.x, min_a.x, max_a.x) {
+            Some((min_b.x, i32::min(max_a.x, max_b.x)))
+        } else if is_between(max_b.x, min_a.x, max_a.x) {
+            Some((i32::max(min_a.x, min_b.x), max_b.x))
+        } else if min_a.x == min_b.x {
+            Some((min_a.x, i32::min(max_a.x, max_b.x)))
+        } else if min_a.x == max_b.x {
+            Some((min_a.x, min_a.x))
+        } else if max_a.x == min_b.x {
+            Some((max_a.x, max_a.x))
         } else {
             None
         };
-        let y = if is_between(min_a.1, min_b.1, max_b.1) {
-            Some((min_a.1, i32::min(max_a.1, max_b.1)))
-        } else if is_between(max_a.1, min_b.1, max_b.1) {
-            Some((i32::max(min_a.1, min_b.1), max_a.1))
-        } else if is_between(min_b.1, min_a.1, max_a.1) {
-            Some((min_b.1, i32::min(max_a.1, max_b.1)))
-        } else if is_between(max_b.1, min_a.1, max_a.1) {
-            Some((i32::max(min_a.1, min_b.1), max_b.1))
-        } else if min_a.1 == min_b.1 {
-            Some((min_a.1, i32::min(max_a.1, max_b.1)))
-        } else if min_a.1 == max_b.1 {
-            Some((min_a.1, min_a.1))
-        } else if max_a.1 == min_b.1 {
-            Some((max_a.1, max_a.1))
+        let y = if is_between(min_a.y, min_b.y, max_b.y) {
+            Some((min_a.y, i32::min(max_a.y, max_b.y)))
+        } else if is_between(max_a.y, min_b.y, max_b.y) {
+            Some((i32::max(min_a.y, min_b.y), max_a.y))
+        } else if is_between(min_b.y, min_a.y, max_a.y) {
+            Some((min_b.y, i32::min(max_a.y, max_b.y)))
+        } else if is_between(max_b.y, min_a.y, max_a.y) {
+            Some((i32::max(min_a.y, min_b.y), max_b.y))
+        } else if min_a.y == min_b.y {
+            Some((min_a.y, i32::min(max_a.y, max_b.y)))
+        } else if min_a.y == max_b.y {
+            Some((min_a.y, min_a.y))
+        } else if max_a.y == min_b.y {
+            Some((max_a.y, max_a.y))
         } else {
             None
         };
-        let z = if is_between(min_a.2, min_b.2, max_b.2) {
-            Some((min_a.2, i32::min(max_a.2, max_b.2)))
-        } else if is_between(max_a.2, min_b.2, max_b.2) {
-            Some((i32::max(min_a.2, min_b.2), max_a.2))
-        } else if is_between(min_b.2, min_a.2, max_a.2) {
-            Some((min_b.2, i32::min(max_a.2, max_b.2)))
-        } else if is_between(max_b.2, min_a.2, max_a.2) {
-            Some((i32::max(min_a.2, min_b.2), max_b.2))
-        } else if min_a.2 == min_b.2 {
-            Some((min_a.2, i32::min(max_a.2, max_b.2)))
-        } else if min_a.2 == max_b.2 {
-            Some((min_a.2, min_a.2))
-        } else if max_a.2 == min_b.2 {
-            Some((max_a.2, max_a.2))
+        let z = if is_between(min_a.z, min_b.z, max_b.z) {
+            Some((min_a.z, i32::min(max_a.z, max_b.z)))
+        } else if is_between(max_a.z, min_b.z, max_b.z) {
+            Some((i32::max(min_a.z, min_b.z), max_a.z))
+        } else if is_between(min_b.z, min_a.z, max_a.z) {
+            Some((min_b.z, i32::min(max_a.z, max_b.z)))
+        } else if is_between(max_b.z, min_a.z, max_a.z) {
+            Some((i32::max(min_a.z, min_b.z), max_b.z))
+        } else if min_a.z == min_b.z {
+            Some((min_a.z, i32::min(max_a.z, max_b.z)))
+        } else if min_a.z == max_b.z {
+            Some((min_a.z, min_a.z))
+        } else if max_a.z == min_b.z {
+            Some((max_a.z, max_a.z))
         } else {            None
         };
 
         match (x, y, z) {
-            (Some(x), Some(y), Some(z)) => Some(Cube(Coord(x.0, y.0, z.0), Coord(x.1, y.1, z.1))),
+            (Some(x), Some(y), Some(z)) => Some(Cube(Vec3::new(x.0, y.0, z.0), Vec3::new(x.1, y.1, z.1))),
             _ => None
         }
     }
@@ -81,42 +83,42 @@ fn is_between(num: i32, min: i32, max: i32) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use crate::day_22::{Coord, Cube};
+    use crate::{day_22::Cube, vec3::Vec3};
     #[test]
     fn test_volume() {
-        let cube = Cube(Coord(0, 0, 0), Coord(2, 2, 2));
+        let cube = Cube(Vec3::new(0, 0, 0), Vec3::new(2, 2, 2));
         assert_eq!(cube.volume(), 27);
     }
     #[test]
     fn test_calc_intersection_cube() {
-        let a = Cube(Coord(0, 0, 0), Coord(3, 3, 3));
-        let b = Cube(Coord(1, 0, 0), Coord(4, 1, 1));
+        let a = Cube(Vec3::new(0, 0, 0), Vec3::new(3, 3, 3));
+        let b = Cube(Vec3::new(1, 0, 0), Vec3::new(4, 1, 1));
         let res = a.calc_intersection_cube(&b);
         let reverse = b.calc_intersection_cube(&a);
         assert_eq!(res, reverse);
-        let expected = Cube(Coord(1, 0, 0), Coord(3, 1, 1));
+        let expected = Cube(Vec3::new(1, 0, 0), Vec3::new(3, 1, 1));
         assert_eq!(res.unwrap(), expected);
 
         assert_eq!(expected.calc_intersection_cube(&expected).unwrap(), expected);
 
-        let b = Cube(Coord(-3, -3, -3), Coord(-1, -1, -1));
+        let b = Cube(Vec3::new(-3, -3, -3), Vec3::new(-1, -1, -1));
         assert_eq!(b.calc_intersection_cube(&a), None);
         assert_eq!(a.calc_intersection_cube(&b), None);
 
         
-        let a = Cube(Coord(0, 3, 0), Coord(3, 6, 1));
-        let b = Cube(Coord(2, 2, 0), Coord(6, 7, 1));
-        let expected = Cube(Coord(2, 3, 0), Coord(3, 6, 1));
+        let a = Cube(Vec3::new(0, 3, 0), Vec3::new(3, 6, 1));
+        let b = Cube(Vec3::new(2, 2, 0), Vec3::new(6, 7, 1));
+        let expected = Cube(Vec3::new(2, 3, 0), Vec3::new(3, 6, 1));
         assert_eq!(a.calc_intersection_cube(&b).unwrap(), expected);
 
-        let a = Cube(Coord(0, 0, 0), Coord(5, 0, 1));
-        let b = Cube(Coord(0, 0, 0), Coord(2, 0, 0));
-        let expected = Cube(Coord(0, 0, 0), Coord(2, 0, 0));
+        let a = Cube(Vec3::new(0, 0, 0), Vec3::new(5, 0, 1));
+        let b = Cube(Vec3::new(0, 0, 0), Vec3::new(2, 0, 0));
+        let expected = Cube(Vec3::new(0, 0, 0), Vec3::new(2, 0, 0));
         assert_eq!(a.calc_intersection_cube(&b).unwrap(), expected);
 
-        let a = Cube(Coord(0, 0, 0), Coord(1, 1, 1));
-        let b = Cube(Coord(0, 1, 0), Coord(2, 2, 1));
-        let expected = Cube(Coord(0, 1, 0), Coord(1, 1, 1));
+        let a = Cube(Vec3::new(0, 0, 0), Vec3::new(1, 1, 1));
+        let b = Cube(Vec3::new(0, 1, 0), Vec3::new(2, 2, 1));
+        let expected = Cube(Vec3::new(0, 1, 0), Vec3::new(1, 1, 1));
         assert_eq!(a.calc_intersection_cube(&b).unwrap(), expected);
     }
 }
\ No newline at end of file
@@ -47,7 +47,7 @@ impl State {
         let mut cur_pos = *start;
         let mut steps = 0;
         while let Some(next_pos) = calc_next_step(&cur_pos, goal) {
-            if self.pods.get(&next_pos).is_some() {
+            if self.pods.contains_key(&next_pos) {
                 return None;
             }
             cur_pos = next_pos;
@@ -1,7 +1,26 @@
-pub fn day_24() {
-    println!("Manually reverse engineer the function and calculate by hand ;)");
-    println!("For the sake of showing that it does something, here's the result for the first round: {:?}",
-        reverse_engineered(1, 0, 0));
+//! Day 24: Arithmetic Logic Unit. Finds the largest and smallest 14 digit model numbers
+//! the MONAD program accepts.
+
+use crate::{solution::Solution, error::ParseError};
+
+pub struct Day24;
+
+// the monad program got reverse engineered by hand, so the input file isn't read at all
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+    type Input = ();
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(_input: &str) -> Result<Self::Input, ParseError> {
+        Ok(())
+    }
+    fn part_1(_input: &Self::Input) -> u64 {
+        find_model_number(true).unwrap()
+    }
+    fn part_2(_input: &Self::Input) -> u64 {
+        find_model_number(false).unwrap()
+    }
 }
 /*
   calculation results per digit for z (same for min and max):
@@ -21,9 +40,34 @@ pub fn day_24() {
     z14:    0                           => resolves w1 and w14
  */
 
+/// Largest or smallest valid model number, found by searching the digits of the reverse
+/// engineered program.
+pub fn find_model_number(largest: bool) -> Option<u64> {
+    let digits: Vec<i64> = if largest { (1..=9).rev().collect() } else { (1..=9).collect() };
+    search_digits(&digits, 0, 1, 0)
+}
+
+fn search_digits(digits: &[i64], z: i64, exec_count: usize, number: u64) -> Option<u64> {
+    if exec_count > 14 {
+        return if z == 0 { Some(number) } else { None };
+    }
+    digits.iter().find_map(|w| {
+        let next_z = reverse_engineered(*w, z, exec_count)?;
+        // every dividing round has to resolve its digit, otherwise z can't get back to 0
+        if is_dividing(exec_count) && next_z > z / 26 {
+            return None;
+        }
+        search_digits(digits, next_z, exec_count + 1, number * 10 + *w as u64)
+    })
+}
+
+fn is_dividing(exec_count: usize) -> bool {
+    matches!(exec_count, 4 | 6 | 7 | 9 | 12 | 13 | 14)
+}
+
 fn reverse_engineered(w: i64, mut z: i64, exec_count: usize) -> Option<i64> {
     let mut x = z % 26;
-    if matches!(exec_count, 4 | 6 | 7 | 9 | 12 | 13 | 14) {
+    if is_dividing(exec_count) {
         z /= 26;
     }
     x += match exec_count {
@@ -63,7 +107,7 @@ fn reverse_engineered(w: i64, mut z: i64, exec_count: usize) -> Option<i64> {
 
 #[cfg(test)]
 mod tests {
-    use super::reverse_engineered;
+    use super::{reverse_engineered, find_model_number};
 
     #[test]
     fn test_part_1() {
@@ -78,6 +122,12 @@ mod tests {
         assert_eq!(Some(0), execute_for_inputs(inputs));
     }
     #[test]
+    fn test_find_model_number() {
+        assert_eq!(find_model_number(true), Some(51983999947999));
+        assert_eq!(find_model_number(false), Some(11211791111365));
+    }
+    #[test]
+    #[ignore = "brute forcing all 14 digit numbers does not finish in a reasonable time"]
     fn test_brute_force() {
         assert_eq!(find_largest_monad_valid(), 51983999947999);
     }
@@ -88,11 +138,7 @@ mod tests {
         let mut round = 0;
         while let Some(val) = values.pop() {
             round += 1;
-            if let Some(cur_z) = reverse_engineered(val as i64, z, round) {
-                z = cur_z;
-            } else {
-                return None;
-            }
+            z = reverse_engineered(val as i64, z, round)?;
         }
         Some(z)
     }
@@ -1,82 +1,89 @@
-use std::{collections::HashSet, fs, fmt::Display};
+//! Day 25: Sea Cucumber. Moves herds of sea cucumbers east and south until none of them
+//! can move anymore.
 
-pub fn day_25() {
-    let world = parse_into_world("./input/day_25.txt");
-    println!("Deadlocked after {} steps!", calc_steps_until_deadlocked(world));
+use std::fmt::Display;
+
+use crate::{solution::{Solution, NoPuzzle}, error::{ParseError, ParseContext}, grid::Grid};
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const HAS_PART_2: bool = false;
+    type Input = World;
+    type Part1 = usize;
+    type Part2 = NoPuzzle;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_into_world(input)
+    }
+    fn part_1(input: &Self::Input) -> usize {
+        calc_steps_until_deadlocked(input)
+    }
+    fn part_2(_input: &Self::Input) -> NoPuzzle {
+        NoPuzzle
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Cell {
+    #[default]
+    Empty,
+    East,
+    South
 }
-// easts, souths, maxima
-struct World(HashSet<(usize, usize)>, HashSet<(usize, usize)>, (usize, usize));
+
+/// The map of both herds, they wrap around at its edges.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct World(pub Grid<Cell>);
 
 impl Display for World {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.2.1 {
-            for x in 0..self.2.0 {
-                if self.0.contains(&(x, y)) {
-                    write!(f, ">")?;
-                } else if self.1.contains(&(x, y)) {
-                    write!(f, "v")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.0.render(|cell| match cell {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v'
+        }))
     }
 }
 
-fn parse_into_world(path: &str) -> World {
-    let input = fs::read_to_string(path).unwrap();
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| line
-            .char_indices()
-            .map(move |(col, ch)| (col, row, ch)))
-        .fold(World(HashSet::new(), HashSet::new(), (0, 0)), |World(mut easts, mut souths, mut max), (x, y, ch)| {
-            match ch {
-                '>' => { easts.insert((x, y)); },
-                'v' => { souths.insert((x, y)); },
-                _ => ()
-            }
-            max = (max.0.max(x + 1), max.1.max(y + 1));
-            World(easts, souths, max)
-        })
+/// Reads the map of `>`, `v` and `.`.
+pub fn parse_into_world(input: &str) -> Result<World, ParseError> {
+    let ctx = ParseContext::new(Day25::DAY, input);
+    Grid::parse(input, &ctx, "'>', 'v' or '.'", |c| match c {
+        '>' => Some(Cell::East),
+        'v' => Some(Cell::South),
+        '.' => Some(Cell::Empty),
+        _ => None
+    }).map(World)
 }
 
-fn exec_step(world: &World) -> Option<World> {
-    let World(easts, souths, max) = world;
-    // println!("{world}");
-    let (new_easts, east_blocked) = easts.iter().fold((HashSet::new(), 0), |(mut new_easts, mut num_blocked), east| {
-        let next = ((east.0 + 1) % max.0, east.1);
-        if easts.contains(&next) || souths.contains(&next) {
-            num_blocked += 1;
-            new_easts.insert(*east);
-        } else {
-            new_easts.insert(next);
-        }
-        (new_easts, num_blocked)
-    });
-    let (new_souths, south_blocked) = souths.iter().fold((HashSet::new(), 0), |(mut new_souths, mut num_blocked), south| {
-        let next = (south.0, (south.1 + 1) % max.1);
-        if new_easts.contains(&next) || souths.contains(&next) {
-            num_blocked += 1;
-            new_souths.insert(*south);
-        } else {
-            new_souths.insert(next);
-        }
-        (new_souths, num_blocked)
-    });
-    if east_blocked + south_blocked == new_easts.len() + new_souths.len() {
-        None
-    } else {
-        Some(World(new_easts, new_souths, *max))
+/// Moves the herd a step in the direction it's facing, returns how many of them moved.
+fn move_herd(grid: &mut Grid<Cell>, herd: Cell, (dx, dy): (i32, i32)) -> usize {
+    let moving: Vec<_> = grid
+        .iter()
+        .filter(|(_, cell)| **cell == herd)
+        .map(|((x, y), _)| ((x, y), grid.wrap((x + dx, y + dy))))
+        .filter(|(_, next)| *grid.at(*next) == Cell::Empty)
+        .collect();
+    for (from, to) in &moving {
+        grid.set(*from, Cell::Empty);
+        grid.set(*to, herd);
     }
+    moving.len()
+}
+
+/// Moves both herds a step, or returns `None` if none of them could move.
+pub fn exec_step(world: &World) -> Option<World> {
+    let mut grid = world.0.clone();
+    let moved = move_herd(&mut grid, Cell::East, (1, 0)) + move_herd(&mut grid, Cell::South, (0, 1));
+    (moved > 0).then_some(World(grid))
 }
 
-fn calc_steps_until_deadlocked(world: World) -> usize {
-    let mut steps = 0;
-    let mut cur_world = Some(world);
+/// Number of the first step on which no sea cucumber moves.
+pub fn calc_steps_until_deadlocked(world: &World) -> usize {
+    let mut steps = 1;
+    let mut cur_world = exec_step(world);
     while let Some(world) = cur_world {
         cur_world = exec_step(&world);
         steps += 1;
@@ -86,11 +93,12 @@ fn calc_steps_until_deadlocked(world: World) -> usize {
 
 #[cfg(test)] 
 mod tests {
+    use std::fs;
     use super::{parse_into_world, calc_steps_until_deadlocked};
 
     #[test]
     fn test_part_1() {
-        let world = parse_into_world("./input/day_25.test.txt");
-        assert_eq!(calc_steps_until_deadlocked(world), 58);
+        let world = parse_into_world(&fs::read_to_string("./input/day_25.test.txt").unwrap()).unwrap();
+        assert_eq!(calc_steps_until_deadlocked(&world), 58);
     }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use crate::bench::{self, Samples};
+use crate::error::ParseError;
+use crate::input::InputSource;
+use crate::solution::{self, Answers, Solution};
+use crate::{day_13::Day13, day_14::Day14, day_15::Day15, day_16::Day16, day_17::Day17, day_18::Day18, day_19::Day19};
+use crate::{day_20::Day20, day_21::Day21, day_22::Day22, day_23::Day23, day_24::Day24, day_25::Day25};
+
+/// Which days to run.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Selection {
+    All,
+    Day(u8)
+}
+
+/// A day's solution with its types erased, so all days can be kept in one list.
+pub struct Day {
+    pub number: u8,
+    pub has_part_2: bool,
+    pub part_2_on_request: bool,
+    pub solve: fn(&str, &[u8]) -> Result<Answers, ParseError>,
+    /// Times each phase of the given parts over a number of runs.
+    pub bench: fn(&str, &[u8], usize) -> Result<Samples, ParseError>
+}
+
+impl Day {
+    /// The parts to solve: `part` if one was asked for, else both, but part 2 only if it
+    /// doesn't wait to be asked for.
+    pub fn parts(&self, part: Option<u8>) -> Vec<u8> {
+        match part {
+            Some(part) => vec![part],
+            None if self.part_2_on_request => vec![1],
+            None => vec![1, 2]
+        }
+    }
+
+    /// Reads the day's input from `source` and solves the given parts.
+    /// Errors describe what went wrong reading or parsing the input.
+    pub fn run(&self, parts: &[u8], source: &InputSource) -> Result<Answers, String> {
+        let input = source
+            .read(self.number)
+            .map_err(|err| format!("couldn't read input {} for day {:02}: {}", source.path(self.number), self.number, err))?;
+        self.solve_input(&input, parts, &source.path(self.number))
+    }
+
+    /// Solves the given parts for an input that was already read from `path`.
+    pub fn solve_input(&self, input: &str, parts: &[u8], path: &str) -> Result<Answers, String> {
+        (self.solve)(input, parts).map_err(|err| format!("couldn't parse input {path}: {err}"))
+    }
+}
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        number: S::DAY,
+        has_part_2: S::HAS_PART_2,
+        part_2_on_request: S::PART_2_ON_REQUEST,
+        solve: solution::solve::<S>,
+        bench: bench::time::<S>
+    }
+}
+
+pub static DAYS: [Day; 13] = [
+    day::<Day13>(),
+    day::<Day14>(),
+    day::<Day15>(),
+    day::<Day16>(),
+    day::<Day17>(),
+    day::<Day18>(),
+    day::<Day19>(),
+    day::<Day20>(),
+    day::<Day21>(),
+    day::<Day22>(),
+    day::<Day23>(),
+    day::<Day24>(),
+    day::<Day25>(),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
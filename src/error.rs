@@ -0,0 +1,124 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Error of a day's input parser, pointing at the offending position in the input.
+/// Lines and columns start at 1.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseError {
+    /// Found something other than what was expected.
+    Unexpected { day: u8, line: usize, column: usize, expected: String, found: String },
+    /// The input ended before something that was expected.
+    UnexpectedEnd { day: u8, line: usize, column: usize, expected: String }
+}
+
+impl ParseError {
+    /// Line and column of the error.
+    pub fn position(&self) -> (usize, usize) {
+        match self {
+            ParseError::Unexpected { line, column, .. } | ParseError::UnexpectedEnd { line, column, .. } => (*line, *column)
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Unexpected { day, line, column, expected, found } =>
+                write!(f, "day {day:02}, line {line}, column {column}: expected {expected}, found {found:?}"),
+            ParseError::UnexpectedEnd { day, line, column, expected } =>
+                write!(f, "day {day:02}, line {line}, column {column}: expected {expected}, found end of input")
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Creates parse errors for a day's input. All the `&str`s passed in have to be slices
+/// of that input (e.g. from `lines`, `split` or `trim`), their position is derived from that.
+pub struct ParseContext<'a> {
+    day: u8,
+    input: &'a str
+}
+
+impl<'a> ParseContext<'a> {
+    pub fn new(day: u8, input: &'a str) -> ParseContext<'a> {
+        ParseContext { day, input }
+    }
+
+    /// `found` is not what was `expected`.
+    pub fn unexpected(&self, found: &str, expected: &str) -> ParseError {
+        let (line, column) = self.position_of(found);
+        if found.is_empty() && self.offset_of(found) >= self.input.trim_end().len() {
+            return ParseError::UnexpectedEnd { day: self.day, line, column, expected: String::from(expected) };
+        }
+        ParseError::Unexpected { day: self.day, line, column, expected: String::from(expected), found: String::from(found) }
+    }
+
+    /// `expected` was missing right after `after`.
+    pub fn missing_after(&self, after: &str, expected: &str) -> ParseError {
+        let end = &self.input[(self.offset_of(after) + after.len()).min(self.input.len())..];
+        let (line, column) = self.position_of(end);
+        if end.trim().is_empty() {
+            return ParseError::UnexpectedEnd { day: self.day, line, column, expected: String::from(expected) };
+        }
+        let found = end.lines().next().unwrap_or_default();
+        ParseError::Unexpected { day: self.day, line, column, expected: String::from(expected), found: String::from(found) }
+    }
+
+    /// Parses `token` as a number, failing with a description of what was `expected`.
+    pub fn number<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse::<T>().map_err(|_| self.unexpected(token, expected))
+    }
+
+    /// Returns the next item of `iter`, or an error pointing right after `after`.
+    pub fn next<I: Iterator>(&self, iter: &mut I, after: &str, expected: &str) -> Result<I::Item, ParseError> {
+        iter.next().ok_or_else(|| self.missing_after(after, expected))
+    }
+
+    fn offset_of(&self, part: &str) -> usize {
+        let start = self.input.as_ptr() as usize;
+        let part_start = part.as_ptr() as usize;
+        if part_start < start || part_start > start + self.input.len() {
+            return self.input.len();
+        }
+        part_start - start
+    }
+
+    fn position_of(&self, part: &str) -> (usize, usize) {
+        let before = &self.input[..self.offset_of(part)];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        (line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "1,2\n3,x\n";
+        let ctx = ParseContext::new(13, input);
+        let token = input.lines().nth(1).unwrap().split(',').nth(1).unwrap();
+        assert_eq!(ctx.number::<i32>(token, "a number"), Err(ParseError::Unexpected {
+            day: 13, line: 2, column: 3, expected: String::from("a number"), found: String::from("x")
+        }));
+    }
+
+    #[test]
+    fn test_missing_after() {
+        let input = "1,2\n3";
+        let ctx = ParseContext::new(13, input);
+        let line = input.lines().nth(1).unwrap();
+        let err = ctx.next(&mut line.split(',').skip(1), line, "','");
+        assert_eq!(err, Err(ParseError::UnexpectedEnd { day: 13, line: 2, column: 2, expected: String::from("','") }));
+        assert_eq!(err.unwrap_err().to_string(), "day 13, line 2, column 2: expected ',', found end of input");
+    }
+
+    #[test]
+    fn test_foreign_slice() {
+        let ctx = ParseContext::new(22, "on x=1..2");
+        let err = ctx.unexpected("elsewhere", "a cube");
+        assert_eq!(err.position(), (1, 10));
+    }
+}
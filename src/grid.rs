@@ -0,0 +1,299 @@
+//! A dense 2D grid shared by the days working on maps of characters.
+
+use crate::error::{ParseContext, ParseError};
+
+/// Position of a cell as (x, y), x growing to the right and y downwards.
+pub type Point = (i32, i32);
+
+/// Which cells count as neighbors of a cell.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Adjacency {
+    /// Left, right, up and down.
+    Orthogonal,
+    /// The orthogonal neighbors and the diagonal ones.
+    All
+}
+
+impl Adjacency {
+    /// Offsets of the neighbors, in reading order.
+    pub fn offsets(self) -> &'static [Point] {
+        match self {
+            Adjacency::Orthogonal => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Adjacency::All => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)]
+        }
+    }
+}
+
+/// Cells stored densely row by row for a rectangle of points, which can start at negative
+/// coordinates. Every point outside of that rectangle has the same `outside` value, so
+/// the grid can stand in for an unbounded plane, and it grows when such a point is set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    /// Top left point of the stored rectangle.
+    min: Point,
+    width: usize,
+    height: usize,
+    outside: T
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid starting at (0, 0), with every cell, including
+    /// the ones outside of it, set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid::with_bounds((0, 0), (width as i32 - 1, height as i32 - 1), fill)
+    }
+
+    /// A grid storing the points from `min` to `max`, both inclusive.
+    pub fn with_bounds(min: Point, max: Point, fill: T) -> Grid<T> {
+        let width = (max.0 - min.0 + 1).max(0) as usize;
+        let height = (max.1 - min.1 + 1).max(0) as usize;
+        Grid { cells: vec![fill.clone(); width * height], min, width, height, outside: fill }
+    }
+
+    /// Creates a grid from its rows, which all need to have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>, outside: T) -> Grid<T> {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "rows of a grid need to have the same length");
+        let height = rows.len();
+        Grid { cells: rows.into_iter().flatten().collect(), min: (0, 0), width, height, outside }
+    }
+
+    /// The smallest grid holding all of the given points set to `value`, all other cells
+    /// are `fill`.
+    pub fn from_points<I: IntoIterator<Item = Point>>(points: I, value: T, fill: T) -> Grid<T> {
+        let points: Vec<Point> = points.into_iter().collect();
+        let min = points.iter().fold((i32::MAX, i32::MAX), |min, p| (min.0.min(p.0), min.1.min(p.1)));
+        let max = points.iter().fold((i32::MIN, i32::MIN), |max, p| (max.0.max(p.0), max.1.max(p.1)));
+        let mut grid = if points.is_empty() { Grid::new(0, 0, fill) } else { Grid::with_bounds(min, max, fill) };
+        for point in points {
+            grid.set(point, value.clone());
+        }
+        grid
+    }
+
+    /// Sets the cell at `point`, growing the grid if it lies outside.
+    pub fn set(&mut self, point: Point, value: T) {
+        if !self.contains(point) {
+            let min = (self.min.0.min(point.0), self.min.1.min(point.1));
+            let max = self.max();
+            self.resize(min, (max.0.max(point.0), max.1.max(point.1)));
+        }
+        let index = self.index(point).unwrap();
+        self.cells[index] = value;
+    }
+
+    /// Changes the stored rectangle to the points from `min` to `max`. Cells that are no
+    /// longer stored get lost, new ones get the outside value.
+    pub fn resize(&mut self, min: Point, max: Point) {
+        let mut resized = Grid::with_bounds(min, max, self.outside.clone());
+        for point in resized.points().collect::<Vec<_>>() {
+            if let Some(value) = self.get(point) {
+                let index = resized.index(point).unwrap();
+                resized.cells[index] = value.clone();
+            }
+        }
+        *self = resized;
+    }
+
+    /// Grows the stored rectangle by `margin` cells on every side.
+    pub fn expand(&mut self, margin: i32) {
+        let max = self.max();
+        self.resize((self.min.0 - margin, self.min.1 - margin), (max.0 + margin, max.1 + margin));
+    }
+
+    /// Same grid with `f` applied to every cell, including the outside value.
+    pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(&f).collect(), min: self.min, width: self.width, height: self.height, outside: f(&self.outside) }
+    }
+}
+
+impl<T: Clone + Default> Grid<T> {
+    /// Parses a map with one character per cell, e.g. `#..#`. All rows need to have the
+    /// same length, and `cell` returns `None` for characters that aren't `expected`.
+    /// The cells outside of the map are `T::default()`.
+    pub fn parse<F: Fn(char) -> Option<T>>(input: &str, ctx: &ParseContext, expected: &str, cell: F) -> Result<Grid<T>, ParseError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let rows = lines
+            .iter()
+            .map(|line| line
+                .char_indices()
+                .map(|(i, c)| cell(c).ok_or_else(|| ctx.unexpected(&line[i..i + c.len_utf8()], expected)))
+                .collect::<Result<Vec<T>, ParseError>>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            return Err(ctx.unexpected(lines[row], &format!("a row of {width} cells")));
+        }
+        Ok(Grid::from_rows(rows, T::default()))
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Top left stored point.
+    pub fn min(&self) -> Point {
+        self.min
+    }
+
+    /// Bottom right stored point.
+    pub fn max(&self) -> Point {
+        (self.min.0 + self.width as i32 - 1, self.min.1 + self.height as i32 - 1)
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x >= self.min.0 && y >= self.min.1 && ((x - self.min.0) as usize) < self.width && ((y - self.min.1) as usize) < self.height
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| (point.1 - self.min.1) as usize * self.width + (point.0 - self.min.0) as usize)
+    }
+
+    /// The stored cell at `point`, `None` outside of the grid.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(|index| &mut self.cells[index])
+    }
+
+    /// The cell at any point of the plane, the outside value beyond the grid.
+    pub fn at(&self, point: Point) -> &T {
+        self.get(point).unwrap_or(&self.outside)
+    }
+
+    pub fn outside(&self) -> &T {
+        &self.outside
+    }
+
+    pub fn set_outside(&mut self, value: T) {
+        self.outside = value;
+    }
+
+    /// All stored points, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let (min, width, height) = (self.min, self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| (min.0 + x, min.1 + y)))
+    }
+
+    /// All stored cells with their points, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Smallest rectangle from its top left to its bottom right point holding all cells
+    /// for which `include` is true, `None` if there aren't any.
+    pub fn bounding_box<F: Fn(&T) -> bool>(&self, include: F) -> Option<(Point, Point)> {
+        self.iter()
+            .filter(|(_, value)| include(value))
+            .fold(None, |bounds, (p, _)| match bounds {
+                None => Some((p, p)),
+                Some((min, max)) => Some(((min.0.min(p.0), min.1.min(p.1)), (max.0.max(p.0), max.1.max(p.1))))
+            })
+    }
+
+    /// The neighbors of `point` that lie within the grid.
+    pub fn neighbors(&self, point: Point, adjacency: Adjacency) -> impl Iterator<Item = Point> + '_ {
+        adjacency
+            .offsets()
+            .iter()
+            .map(move |(dx, dy)| (point.0 + dx, point.1 + dy))
+            .filter(|neighbor| self.contains(*neighbor))
+    }
+
+    /// The neighbors of `point`, wrapping around at the edges of the grid like on a torus.
+    pub fn wrapping_neighbors(&self, point: Point, adjacency: Adjacency) -> impl Iterator<Item = Point> + '_ {
+        adjacency
+            .offsets()
+            .iter()
+            .map(move |(dx, dy)| self.wrap((point.0 + dx, point.1 + dy)))
+    }
+
+    /// Moves `point` into the grid by wrapping it around its edges.
+    pub fn wrap(&self, (x, y): Point) -> Point {
+        (
+            self.min.0 + (x - self.min.0).rem_euclid(self.width as i32),
+            self.min.1 + (y - self.min.1).rem_euclid(self.height as i32)
+        )
+    }
+
+    /// Draws the grid with one character per cell and a line break after each row.
+    pub fn render<F: Fn(&T) -> char>(&self, cell: F) -> String {
+        self.render_area(self.min, self.max(), cell)
+    }
+
+    /// Draws the points from `min` to `max` of the plane, which may reach beyond the grid.
+    pub fn render_area<F: Fn(&T) -> char>(&self, min: Point, max: Point, cell: F) -> String {
+        let mut result = String::new();
+        for y in min.1..=max.1 {
+            result.extend((min.0..=max.0).map(|x| cell(self.at((x, y)))));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(map: &str) -> Grid<bool> {
+        Grid::parse(map, &ParseContext::new(20, map), "'#' or '.'", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid = parse("#..\n.#.\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((1, 1)), Some(&true));
+        assert_eq!(grid.get((3, 1)), None);
+        assert!(!grid.at((-5, 7)));
+        assert_eq!(grid.render(|on| if *on { '#' } else { '.' }), "#..\n.#.\n");
+        assert_eq!(grid.render_area((-1, 0), (1, 0), |on| if *on { '#' } else { '.' }), ".#.\n");
+        let map = "#.\n#x\n";
+        let err = Grid::parse(map, &ParseContext::new(20, map), "'#' or '.'", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!(err.position(), (2, 2));
+        let map = "#.\n#\n";
+        let err = Grid::parse(map, &ParseContext::new(20, map), "'#' or '.'", Some).unwrap_err();
+        assert_eq!(err.to_string(), "day 20, line 2, column 1: expected a row of 2 cells, found \"#\"");
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let mut grid = Grid::from_points([(2, 3), (-1, 0)], 1, 0);
+        assert_eq!((grid.min(), grid.max()), ((-1, 0), (2, 3)));
+        grid.set((-3, 5), 2);
+        assert_eq!((grid.min(), grid.max()), ((-3, 0), (2, 5)));
+        assert_eq!(grid.iter().filter(|(_, value)| **value > 0).count(), 3);
+        assert_eq!(*grid.at((-3, 5)), 2);
+        assert_eq!(grid.bounding_box(|value| *value == 1), Some(((-1, 0), (2, 3))));
+        assert_eq!(grid.bounding_box(|value| *value == 3), None);
+        grid.set_outside(7);
+        grid.expand(1);
+        assert_eq!((grid.width(), grid.height()), (8, 8));
+        assert_eq!(grid.get((-4, -1)), Some(&7));
+        assert_eq!(grid.map(|value| value * 2).at((100, 100)), &14);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbors((0, 0), Adjacency::Orthogonal).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors((1, 1), Adjacency::All).count(), 8);
+        assert_eq!(grid.neighbors((2, 2), Adjacency::All).collect::<Vec<_>>(), vec![(1, 1), (2, 1), (1, 2)]);
+        assert_eq!(grid.wrapping_neighbors((0, 0), Adjacency::Orthogonal).collect::<Vec<_>>(), vec![(0, 2), (2, 0), (1, 0), (0, 1)]);
+        assert_eq!(grid.wrap((-1, 7)), (2, 1));
+    }
+}
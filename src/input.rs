@@ -0,0 +1,57 @@
+use std::{fs, io::{self, Read}};
+
+pub const DEFAULT_DIR: &str = "./input";
+
+/// Where a day reads its puzzle input from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InputSource {
+    /// Directory containing `day_XX.txt` files.
+    Dir(String),
+    File(String),
+    Stdin
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Dir(String::from(DEFAULT_DIR))
+    }
+}
+
+impl InputSource {
+    pub fn path(&self, day: u8) -> String {
+        match self {
+            InputSource::Dir(dir) => format!("{}/day_{:02}.txt", dir.trim_end_matches('/'), day),
+            InputSource::File(path) => path.clone(),
+            InputSource::Stdin => String::from("-")
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self {
+            InputSource::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                Ok(contents)
+            },
+            _ => fs::read_to_string(self.path(day))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        assert_eq!(InputSource::default().path(13), "./input/day_13.txt");
+        assert_eq!(InputSource::Dir(String::from("fixtures/")).path(22), "fixtures/day_22.txt");
+        assert_eq!(InputSource::File(String::from("mine.txt")).path(22), "mine.txt");
+    }
+
+    #[test]
+    fn test_read() {
+        let contents = InputSource::default().read(17).unwrap();
+        assert_eq!(contents.trim(), "target area: x=150..193, y=-136..-86");
+    }
+}
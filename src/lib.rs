@@ -0,0 +1,33 @@
+//! Solutions for days 13 to 25 of Advent of Code 2021.
+//!
+//! Every day has its own module exposing its input parser and the algorithms solving it,
+//! plus a `DayXX` type implementing [`solution::Solution`]. [`days::DAYS`] lists all of
+//! them for running them by number.
+#![feature(linked_list_cursors)]
+
+pub mod bench;
+pub mod days;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod log;
+pub mod parallel;
+pub mod pathfinder;
+pub mod report;
+pub mod solution;
+pub mod vec3;
+pub mod verify;
+
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the days report besides their answers. All messages go to stderr,
+/// so stdout only ever contains the answers.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Level {
+    /// `--quiet`: nothing but the answers and errors.
+    Quiet,
+    /// Default: warnings about suspicious input or results.
+    Normal,
+    /// `-v`: progress of the solvers.
+    Verbose,
+    /// `-vv`: every intermediate step, can be a lot of output and slow down the solvers.
+    Debug
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Logs at `Level::Normal`. Like the other macros, the arguments are only evaluated
+/// if the level is enabled.
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Normal) {
+            eprintln!("warning: {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs at `Level::Verbose`.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Verbose) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Logs at `Level::Debug`.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {debug, info, warning};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert!(Level::Quiet < Level::Normal && Level::Verbose < Level::Debug);
+        assert!(enabled(Level::Quiet));
+        assert!(!enabled(Level::Debug));
+    }
+}
@@ -1,45 +1,215 @@
-#![feature(linked_list_cursors)]
-#![feature(map_many_mut)]
-#![feature(is_some_and)]
-
-// mod day_13;
-// mod day_14;
-// mod day_15;
-// mod day_16;
-// mod day_17;
-// mod day_18;
-// mod day_19;
-// mod day_20;
-// mod day_21;
-// mod day_22;
-// mod day_23;
-// mod day_24;
-mod day_25;
+use std::{env, io::{self, IsTerminal}, path::Path, process};
+
+use aoc2021::{bench, day_13, day_15, days::{self, Day, Selection}, input::InputSource, log, parallel::{self, PartResult, Status}, report::{self, Format}, verify};
+use cli::{Command, RunOptions};
+
+mod cli;
 
 fn main() {
-    // day_13::part_1();
-    // day_13::part_2();
-    // day_14::part_1();
-    // day_14::part_2();
-    // day_15::part_1();
-    // day_15::part_2();
-    // day_16::part_1();
-    // day_16::part_2();
-    // day_17::part_1();
-    // day_17::part_2();
-    // day_18::part_1();
-    // day_18::part_2();
-    // day_19::part_1();
-    // day_19::part_2();
-    // day_20::part_1();
-    // day_20::part_2();
-    // day_21::part_1();
-    // day_21::part_2();
-    // day_22::part_1();
-    // day_22::part_2();
-    // day_23::part_1();
-    // day_23::part_2();
-    // day_23::day_23();
-    // day_24::day_24();
-    day_25::day_25();
-}
\ No newline at end of file
+    let command = match cli::split_verbosity(env::args().skip(1)).and_then(|(level, args)| {
+        log::set_level(level);
+        cli::parse_args(args)
+    }) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+    match command {
+        Command::List => list(),
+        Command::Run { selection, part, input, options: RunOptions { format, jobs, timeout, frames, route } } => {
+            if let Some(dir) = &frames {
+                write_frames(&input, dir);
+            }
+            if let Some(file) = &route {
+                write_route(&input, file, part);
+            }
+            if let Some(jobs) = jobs {
+                let results = parallel::run_parallel(&select(selection), part, &input, jobs, timeout, |result| print_result(result, format));
+                if format == Format::Text {
+                    println!("\n{}", parallel::summary_table(&results).trim_end());
+                }
+                if results.iter().any(|result| result.status == Status::Error) {
+                    process::exit(1);
+                }
+                return;
+            }
+            let mut ok = true;
+            for day in select(selection) {
+                ok &= run(day, part, &input, format);
+            }
+            if !ok {
+                process::exit(1);
+            }
+        },
+        Command::Bench { selection, part, input, options } => {
+            let mut ok = bench::bench(&select(selection), part, &input, &options);
+            if options.solvers {
+                ok &= bench_solvers(&input, part, options.runs);
+            }
+            if !ok {
+                process::exit(1);
+            }
+        },
+        Command::Verify { selection, answers } => {
+            if !verify::verify(&answers, selection) {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn select(selection: Selection) -> Vec<&'static Day> {
+    match selection {
+        Selection::All => days::DAYS.iter().collect(),
+        Selection::Day(number) => match days::find(number) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("error: day {number} is not available, see 'aoc2021 list'");
+                process::exit(2);
+            }
+        }
+    }
+}
+
+fn list() {
+    for day in days::DAYS.iter() {
+        let parts = match (day.has_part_2, day.part_2_on_request) {
+            (false, _) => "1",
+            (true, true) => "1, 2 (only with --part 2)",
+            (true, false) => "1, 2"
+        };
+        println!("day {:02}: parts {}", day.number, parts);
+    }
+}
+
+/// Solves and prints the parts of a day, `false` if its input couldn't be read or parsed.
+fn run(day: &Day, part: Option<u8>, source: &InputSource, format: Format) -> bool {
+    let parts = day.parts(part);
+    let result = day.run(&parts, source);
+    if format == Format::Json {
+        for object in report::to_json(day.number, &parts, &source.path(day.number), &result) {
+            println!("{object}");
+        }
+        return result.is_ok();
+    }
+    let answers = match result {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return false;
+        }
+    };
+    for answer in answers.parts {
+        println!("day {:02}, part {}: {}", day.number, answer.part, answer.value);
+    }
+    if parts.contains(&2) && !day.has_part_2 {
+        println!("day {:02}, part 2: not available", day.number);
+    }
+    if !parts.contains(&2) && part.is_none() && day.has_part_2 {
+        println!("day {:02}, part 2: skipped, run it with --part 2", day.number);
+    }
+    true
+}
+
+/// Prints the result of a single part as soon as it was solved by `--jobs`.
+fn print_result(result: &PartResult, format: Format) {
+    if format == Format::Json {
+        for object in report::to_json(result.day, &[result.part], &result.input, &result.result) {
+            println!("{object}");
+        }
+        return;
+    }
+    match (&result.result, result.status) {
+        (Ok(answers), Status::Ok) => println!("day {:02}, part {}: {}", result.day, result.part, answers.parts[0].value),
+        (_, Status::NotAvailable) => println!("day {:02}, part {}: not available", result.day, result.part),
+        (Err(err), _) => eprintln!("error: {err}"),
+        (Ok(_), _) => ()
+    }
+}
+
+/// Writes the frames of day 13's folds and prints the statistics of each fold.
+fn write_frames(source: &InputSource, dir: &str) {
+    let input = match source.read(13) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: couldn't read input {} for day 13: {}", source.path(13), err);
+            return;
+        }
+    };
+    let (folds, dots) = match day_13::read_input(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("error: couldn't parse input {}: {}", source.path(13), err);
+            return;
+        }
+    };
+    match day_13::write_frames(&folds, &dots, Path::new(dir)) {
+        Ok(steps) => {
+            for step in &steps {
+                println!("fold {}: {}, {} dots, {} merged, {}x{}", step.number, step.fold, step.dots, step.merged, step.width, step.height);
+            }
+            println!("wrote {} frames to {}", steps.len() + 1, dir);
+        },
+        Err(err) => eprintln!("error: couldn't write frames to {dir}: {err}")
+    }
+}
+
+/// Finds day 15's lowest-risk route through the cave of `part`, writes it to `file` and
+/// shows it on the terminal.
+fn write_route(source: &InputSource, file: &str, part: Option<u8>) {
+    let input = match source.read(15) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: couldn't read input {} for day 15: {}", source.path(15), err);
+            return;
+        }
+    };
+    let tile = match day_15::read_input(&input) {
+        Ok(tile) => tile,
+        Err(err) => {
+            eprintln!("error: couldn't parse input {}: {}", source.path(15), err);
+            return;
+        }
+    };
+    let cave = if part == Some(2) { day_15::expand_cave(&tile) } else { tile };
+    let route = day_15::find_fastest_route(&cave);
+    if io::stdout().is_terminal() {
+        print!("{}", day_15::render_route(&cave, &route.path, true));
+    }
+    match day_15::write_route(&cave, &route, Path::new(file)) {
+        Ok(()) => println!("route with risk {} over {} positions, wrote it to {}", route.risk, route.path.len(), file),
+        Err(err) => eprintln!("error: couldn't write route to {file}: {err}")
+    }
+}
+
+/// Times each of day 15's solvers on the cave of `part` and prints their stats. Returns
+/// whether the input could be read and all solvers found the same lowest total risk.
+fn bench_solvers(source: &InputSource, part: Option<u8>, runs: usize) -> bool {
+    let input = match source.read(15) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: couldn't read input {} for day 15: {}", source.path(15), err);
+            return false;
+        }
+    };
+    let tile = match day_15::read_input(&input) {
+        Ok(tile) => tile,
+        Err(err) => {
+            eprintln!("error: couldn't parse input {}: {}", source.path(15), err);
+            return false;
+        }
+    };
+    let cave = if part == Some(2) { day_15::expand_cave(&tile) } else { tile };
+    let results = day_15::compare_solvers(&cave, runs);
+    for (solver, risk, stats) in &results {
+        println!("day 15, {:<8}  min {:>10.2?}  median {:>10.2?}  max {:>10.2?}  risk {}",
+            solver.name(), stats.min, stats.median, stats.max, risk);
+    }
+    if results.iter().any(|(_, risk, _)| *risk != results[0].1) {
+        eprintln!("error: day 15's solvers found different lowest total risks");
+        return false;
+    }
+    true
+}
@@ -0,0 +1,198 @@
+use std::{collections::{HashSet, VecDeque}, fmt::Display, panic::{self, AssertUnwindSafe}};
+use std::{sync::{mpsc, Arc, Mutex}, thread, time::{Duration, Instant}};
+
+use crate::{days::Day, input::InputSource, solution::Answers};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Ok,
+    /// The input couldn't be read or parsed, or the solver panicked.
+    Error,
+    /// The day has no such part.
+    NotAvailable,
+    /// The part didn't finish within the timeout.
+    Timeout
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Error => write!(f, "error"),
+            Status::NotAvailable => write!(f, "n/a"),
+            Status::Timeout => write!(f, "timeout")
+        }
+    }
+}
+
+/// Outcome of solving a single part of a day.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    /// Path the day's input was read from.
+    pub input: String,
+    pub status: Status,
+    /// Answers holding just this part, with the timings to parse and solve it.
+    pub result: Result<Answers, String>
+}
+
+impl PartResult {
+    fn new(day: u8, part: u8, input: String, result: Result<Answers, String>) -> PartResult {
+        let status = match &result {
+            Ok(answers) if answers.parts.is_empty() => Status::NotAvailable,
+            Ok(_) => Status::Ok,
+            Err(_) => Status::Error
+        };
+        PartResult { day, part, input, status, result }
+    }
+}
+
+struct Job {
+    day: &'static Day,
+    part: u8,
+    input: Arc<String>,
+    path: String
+}
+
+/// Solves every part of every day as its own job on a pool of `jobs` threads, so a slow
+/// part only keeps its own thread busy. `on_result` is called as soon as a part finishes.
+/// Parts still running after `timeout` are reported as timed out and left behind.
+/// Returns the results ordered by day and part.
+pub fn run_parallel<F: FnMut(&PartResult)>(days: &[&'static Day], part: Option<u8>, source: &InputSource,
+    jobs: usize, timeout: Option<Duration>, mut on_result: F) -> Vec<PartResult> {
+    let mut results = vec![];
+    let mut queue = VecDeque::new();
+    // inputs are read up front, so stdin is only read once
+    for day in days {
+        let parts = day.parts(part);
+        let path = source.path(day.number);
+        match source.read(day.number) {
+            Ok(input) => {
+                let input = Arc::new(input);
+                queue.extend(parts.iter().map(|part| Job { day, part: *part, input: Arc::clone(&input), path: path.clone() }));
+            },
+            Err(err) => {
+                let err = format!("couldn't read input {} for day {:02}: {}", path, day.number, err);
+                for part in parts {
+                    let result = PartResult::new(day.number, part, path.clone(), Err(err.clone()));
+                    on_result(&result);
+                    results.push(result);
+                }
+            }
+        }
+    }
+    let mut pending: HashSet<(u8, u8)> = queue.iter().map(|job| (job.day.number, job.part)).collect();
+    let paths: Vec<(u8, String)> = queue.iter().map(|job| (job.day.number, job.path.clone())).collect();
+    let queue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..jobs.min(pending.len()) {
+        let queue = Arc::clone(&queue);
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let Some(job) = queue.lock().unwrap().pop_front() else {
+                break;
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| job.day.solve_input(&job.input, &[job.part], &job.path)))
+                .unwrap_or_else(|_| Err(format!("day {:02} part {} panicked", job.day.number, job.part)));
+            if sender.send(PartResult::new(job.day.number, job.part, job.path, result)).is_err() {
+                break;
+            }
+        });
+    }
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    while !pending.is_empty() {
+        let received = match deadline {
+            Some(deadline) => receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok(),
+            None => receiver.recv().ok()
+        };
+        let Some(result) = received else {
+            break;
+        };
+        pending.remove(&(result.day, result.part));
+        on_result(&result);
+        results.push(result);
+    }
+    for (day, part) in pending {
+        let path = paths.iter().find(|(number, _)| *number == day).map(|(_, path)| path.clone()).unwrap_or_default();
+        let err = format!("day {:02} part {} didn't finish within {:.2?}", day, part, timeout.unwrap_or_default());
+        let result = PartResult { day, part, input: path, status: Status::Timeout, result: Err(err) };
+        on_result(&result);
+        results.push(result);
+    }
+    results.sort_by_key(|result| (result.day, result.part));
+    results
+}
+
+/// Renders the results as a table with the day, part, answer, time and status of each part.
+/// The time includes parsing the input.
+pub fn summary_table(results: &[PartResult]) -> String {
+    let rows: Vec<[String; 5]> = results
+        .iter()
+        .map(|result| {
+            let (answer, time) = match &result.result {
+                Ok(answers) => match answers.parts.first() {
+                    Some(answer) if answer.value.contains('\n') => (String::from("(multiple lines)"), Some(answers.parse_time + answer.time)),
+                    Some(answer) => (answer.value.clone(), Some(answers.parse_time + answer.time)),
+                    None => (String::new(), None)
+                },
+                Err(_) => (String::new(), None)
+            };
+            let time = time.map(|time| format!("{time:.2?}")).unwrap_or_default();
+            [result.day.to_string(), result.part.to_string(), answer, time, result.status.to_string()]
+        })
+        .collect();
+    let header = [String::from("day"), String::from("part"), String::from("answer"), String::from("time"), String::from("status")];
+    let widths: Vec<usize> = (0..5)
+        .map(|column| rows.iter().chain([&header]).map(|row| row[column].chars().count()).max().unwrap())
+        .collect();
+    let mut table = String::new();
+    for row in [&header].into_iter().chain(rows.iter()) {
+        let line = format!("{:>w0$}  {:>w1$}  {:<w2$}  {:>w3$}  {}",
+            row[0], row[1], row[2], row[3], row[4], w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]);
+        table += line.trim_end();
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{days, solution::Answer};
+
+    #[test]
+    fn test_run_parallel() {
+        let selected = [days::find(17).unwrap(), days::find(25).unwrap()];
+        let source = InputSource::Dir(String::from("./input"));
+        let mut streamed = 0;
+        let results = run_parallel(&selected, None, &source, 3, None, |_| streamed += 1);
+        assert_eq!(streamed, 4);
+        let statuses: Vec<(u8, u8, Status)> = results.iter().map(|result| (result.day, result.part, result.status)).collect();
+        assert_eq!(statuses, vec![(17, 1, Status::Ok), (17, 2, Status::Ok), (25, 1, Status::Ok), (25, 2, Status::NotAvailable)]);
+        assert_eq!(results[0].result.as_ref().unwrap().parts[0].value, "9180");
+    }
+
+    #[test]
+    fn test_run_parallel_missing_input() {
+        let selected = [days::find(17).unwrap()];
+        let source = InputSource::Dir(String::from("./no_such_dir"));
+        let results = run_parallel(&selected, Some(1), &source, 2, Some(Duration::from_secs(1)), |_| ());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, Status::Error);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let answers = Answers { parse_time: Duration::from_millis(1), parts: vec![Answer { part: 1, value: String::from("9180"), time: Duration::from_millis(2) }] };
+        let results = vec![
+            PartResult::new(17, 1, String::from("day_17.txt"), Ok(answers)),
+            PartResult { day: 23, part: 2, input: String::from("day_23.txt"), status: Status::Timeout, result: Err(String::from("slow")) }
+        ];
+        assert_eq!(summary_table(&results), "\
+day  part  answer    time  status
+ 17     1  9180    3.00ms  ok
+ 23     2                  timeout
+");
+    }
+}
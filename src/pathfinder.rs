@@ -0,0 +1,416 @@
+//! Cheapest paths through a grid, with configurable moves and prices for them. Day 15
+//! uses it for its variations, other grid puzzles can plug in their own rules.
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet}};
+
+use crate::grid::{Adjacency, Grid, Point};
+
+/// Where a single move can go from a cell.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Movement {
+    /// To the neighbors, orthogonal or also diagonal.
+    Adjacent(Adjacency),
+    /// Like a knight in chess, two cells in one direction and one to the side, jumping
+    /// over the cells in between.
+    Knight,
+    /// By any of the given offsets.
+    Offsets(Vec<Point>)
+}
+
+impl Movement {
+    pub fn offsets(&self) -> Vec<Point> {
+        match self {
+            Movement::Adjacent(adjacency) => adjacency.offsets().to_vec(),
+            Movement::Knight => vec![(1, -2), (2, -1), (2, 1), (1, 2), (-1, 2), (-2, 1), (-2, -1), (-1, -2)],
+            Movement::Offsets(offsets) => offsets.clone()
+        }
+    }
+}
+
+/// A move to price.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Step {
+    pub from: Point,
+    pub to: Point,
+    /// Whether the move goes another way than the one before. The first move doesn't turn.
+    pub turned: bool
+}
+
+/// Prices moves. `None` forbids a move, e.g. into a wall.
+pub trait Cost<T> {
+    fn cost(&self, grid: &Grid<T>, step: &Step) -> Option<u64>;
+}
+
+impl<T, F: Fn(&Grid<T>, &Step) -> Option<u64>> Cost<T> for F {
+    fn cost(&self, grid: &Grid<T>, step: &Step) -> Option<u64> {
+        self(grid, step)
+    }
+}
+
+/// Moving costs the value of the cell moved into, like the risk levels of day 15.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Enter;
+
+impl<T: Copy + Into<u64>> Cost<T> for Enter {
+    fn cost(&self, grid: &Grid<T>, step: &Step) -> Option<u64> {
+        grid.get(step.to).map(|value| (*value).into())
+    }
+}
+
+/// Moving costs the value of the cell moved out of.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Exit;
+
+impl<T: Copy + Into<u64>> Cost<T> for Exit {
+    fn cost(&self, grid: &Grid<T>, step: &Step) -> Option<u64> {
+        grid.get(step.from).map(|value| (*value).into())
+    }
+}
+
+/// Another cost, plus a penalty for every turn.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TurnPenalty<C>(pub C, pub u64);
+
+impl<T, C: Cost<T>> Cost<T> for TurnPenalty<C> {
+    fn cost(&self, grid: &Grid<T>, step: &Step) -> Option<u64> {
+        self.0.cost(grid, step).map(|cost| if step.turned { cost + self.1 } else { cost })
+    }
+}
+
+/// A path through the grid and the total cost of its moves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Route {
+    pub cost: u64,
+    /// Every cell on the way, from the start to the goal.
+    pub path: Vec<Point>
+}
+
+/// The rules for searching the cheapest path.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pathfinder<C> {
+    pub movement: Movement,
+    /// How many moves in a row can go the same way, any number if `None`.
+    pub max_straight: Option<usize>,
+    pub cost: C
+}
+
+/// A position in the search: the cell, the offset of the move that got there, and how
+/// many moves in a row went that way. The run is only counted with a `max_straight`.
+type State = (Point, Option<usize>, usize);
+
+impl<C> Pathfinder<C> {
+    /// Orthogonal moves without a limit on going straight.
+    pub fn new(cost: C) -> Pathfinder<C> {
+        Pathfinder { movement: Movement::Adjacent(Adjacency::Orthogonal), max_straight: None, cost }
+    }
+
+    /// The cheapest route from `start` to `goal` by Dijkstra's algorithm, `None` if the
+    /// goal can't be reached.
+    pub fn route<T: Clone>(&self, grid: &Grid<T>, start: Point, goal: Point) -> Option<Route>
+    where
+        C: Cost<T>
+    {
+        self.route_between(grid, &[start], &[goal])
+    }
+
+    /// The cheapest route from any of the starts to any of the goals.
+    pub fn route_between<T: Clone>(&self, grid: &Grid<T>, starts: &[Point], goals: &[Point]) -> Option<Route>
+    where
+        C: Cost<T>
+    {
+        let starts: Vec<(State, u64)> = starts.iter().map(|start| ((*start, None, 0), 0)).collect();
+        self.search(grid, &starts, &Blocked::default(), |point| goals.contains(&point)).route
+    }
+
+    /// The cheapest cost from any of the starts to every cell, `None` for cells that
+    /// can't be reached.
+    pub fn distances<T: Clone>(&self, grid: &Grid<T>, starts: &[Point]) -> Grid<Option<u64>>
+    where
+        C: Cost<T>
+    {
+        let starts: Vec<(State, u64)> = starts.iter().map(|start| ((*start, None, 0), 0)).collect();
+        let mut distances = grid.map(|_| None);
+        for ((point, _, _), dist) in self.search(grid, &starts, &Blocked::default(), |_| false).dists {
+            if distances.at(point).is_none_or(|best| dist < best) {
+                distances.set(point, Some(dist));
+            }
+        }
+        distances
+    }
+
+    /// The `k` cheapest routes from `start` to `goal`, cheapest first, by Yen's algorithm.
+    /// Fewer if there aren't that many. Without a `max_straight` the routes don't visit
+    /// any cell twice. With one, going back and forth can be the cheapest way, so routes
+    /// can revisit cells, and since a route never goes back into the part it shares with
+    /// an earlier one, some routes can be missed.
+    pub fn k_routes<T: Clone>(&self, grid: &Grid<T>, start: Point, goal: Point, k: usize) -> Vec<Route>
+    where
+        C: Cost<T>
+    {
+        let mut routes: Vec<Route> = self.route(grid, start, goal).into_iter().take(k).collect();
+        let mut candidates: Vec<Route> = vec![];
+        while routes.len() < k {
+            let Some(Route { path: last, .. }) = routes.last() else {
+                break;
+            };
+            for spur in 0..last.len() - 1 {
+                let root = &last[..=spur];
+                // don't take a way already taken from this root, and don't go back into it
+                let blocked = Blocked {
+                    cells: root[..spur].iter().copied().collect(),
+                    moves: routes
+                        .iter()
+                        .filter(|route| route.path.len() > spur + 1 && route.path[..=spur] == *root)
+                        .map(|route| (route.path[spur], route.path[spur + 1]))
+                        .collect()
+                };
+                let Some(start) = self.replay(grid, root) else {
+                    continue;
+                };
+                let Some(spur_route) = self.search(grid, &[start], &blocked, |point| point == goal).route else {
+                    continue;
+                };
+                let path: Vec<Point> = root[..spur].iter().copied().chain(spur_route.path).collect();
+                if !routes.iter().chain(&candidates).any(|route| route.path == path) {
+                    candidates.push(Route { cost: spur_route.cost, path });
+                }
+            }
+            let Some(cheapest) = (0..candidates.len()).min_by_key(|index| (candidates[*index].cost, candidates[*index].path.len())) else {
+                break;
+            };
+            routes.push(candidates.swap_remove(cheapest));
+        }
+        routes
+    }
+
+    /// Follows a path from its first cell, returning the state at its end and its cost.
+    /// `None` if the rules don't allow the path.
+    fn replay<T: Clone>(&self, grid: &Grid<T>, path: &[Point]) -> Option<(State, u64)>
+    where
+        C: Cost<T>
+    {
+        let offsets = self.movement.offsets();
+        let mut state: State = (path[0], None, 0);
+        let mut total = 0;
+        for to in &path[1..] {
+            let point = state.0;
+            let index = offsets.iter().position(|(dx, dy)| (point.0 + dx, point.1 + dy) == *to)?;
+            let (step, next_run) = self.step(state, index, *to)?;
+            total += self.cost.cost(grid, &step)?;
+            state = (*to, Some(index), next_run);
+        }
+        Some((state, total))
+    }
+
+    /// The move from a state by the offset with the given index, and the run after it.
+    /// `None` if it would go straight for too long.
+    fn step(&self, (from, direction, run): State, index: usize, to: Point) -> Option<(Step, usize)> {
+        let straight = direction == Some(index);
+        let next_run = match self.max_straight {
+            Some(max) if straight && run >= max => return None,
+            Some(_) if straight => run + 1,
+            Some(_) => 1,
+            None => 0
+        };
+        Some((Step { from, to, turned: direction.is_some() && !straight }, next_run))
+    }
+
+    /// Dijkstra's algorithm from the given states and costs, until reaching a cell for
+    /// which `is_goal` holds.
+    fn search<T: Clone>(&self, grid: &Grid<T>, starts: &[(State, u64)], blocked: &Blocked, is_goal: impl Fn(Point) -> bool) -> Search
+    where
+        C: Cost<T>
+    {
+        let offsets = self.movement.offsets();
+        let mut dists: HashMap<State, u64> = HashMap::new();
+        for (state, dist) in starts {
+            dists.insert(*state, *dist);
+        }
+        let mut previous: HashMap<State, State> = HashMap::new();
+        let mut queue: BinaryHeap<_> = starts.iter().map(|(state, dist)| Reverse((*dist, *state))).collect();
+        while let Some(Reverse((dist, state))) = queue.pop() {
+            let point = state.0;
+            if is_goal(point) {
+                let mut path = vec![point];
+                let mut current = state;
+                while let Some(before) = previous.get(&current) {
+                    path.push(before.0);
+                    current = *before;
+                }
+                path.reverse();
+                return Search { route: Some(Route { cost: dist, path }), dists };
+            }
+            // a stale entry, the state was reached cheaper since
+            if dist > dists[&state] {
+                continue;
+            }
+            for (index, (dx, dy)) in offsets.iter().enumerate() {
+                let to = (point.0 + dx, point.1 + dy);
+                if !grid.contains(to) || blocked.cells.contains(&to) || blocked.moves.contains(&(point, to)) {
+                    continue;
+                }
+                let Some((step, next_run)) = self.step(state, index, to) else {
+                    continue;
+                };
+                let Some(cost) = self.cost.cost(grid, &step) else {
+                    continue;
+                };
+                let next = (to, Some(index), next_run);
+                let sum = dist + cost;
+                if dists.get(&next).is_none_or(|dist| sum < *dist) {
+                    dists.insert(next, sum);
+                    previous.insert(next, state);
+                    queue.push(Reverse((sum, next)));
+                }
+            }
+        }
+        Search { route: None, dists }
+    }
+}
+
+/// Cells and moves a search must not use.
+#[derive(Debug, Default)]
+struct Blocked {
+    cells: HashSet<Point>,
+    moves: HashSet<(Point, Point)>
+}
+
+/// The outcome of a search: the route to a goal if it found one, and the cost to every
+/// state it reached on the way.
+struct Search {
+    route: Option<Route>,
+    dists: HashMap<State, u64>
+}
+
+/// The distances as comma separated values, one line per row, unreachable cells empty.
+pub fn distances_to_csv(distances: &Grid<Option<u64>>) -> String {
+    let (min, max) = (distances.min(), distances.max());
+    let mut csv = String::new();
+    for y in min.1..=max.1 {
+        let row: Vec<String> = (min.0..=max.0).map(|x| distances.at((x, y)).map_or(String::new(), |dist| dist.to_string())).collect();
+        csv += &row.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &str) -> Grid<u8> {
+        Grid::from_rows(rows.lines().map(|row| row.bytes().map(|b| b - b'0').collect()).collect(), 0)
+    }
+
+    #[test]
+    fn test_movement() {
+        let cave = grid("1911\n1919\n1119");
+        let orthogonal = Pathfinder::new(Enter);
+        assert_eq!(orthogonal.route(&cave, (0, 0), (3, 0)).unwrap().cost, 7);
+        let all = Pathfinder { movement: Movement::Adjacent(Adjacency::All), ..Pathfinder::new(Enter) };
+        let route = all.route(&cave, (0, 0), (3, 0)).unwrap();
+        assert_eq!(route.cost, 4);
+        assert_eq!(route.path, vec![(0, 0), (0, 1), (1, 2), (2, 1), (3, 0)]);
+        let knight = Pathfinder { movement: Movement::Knight, ..Pathfinder::new(Enter) };
+        assert_eq!(knight.route(&cave, (0, 0), (2, 1)), Some(Route { cost: 1, path: vec![(0, 0), (2, 1)] }));
+        assert_eq!(knight.route(&grid("11\n11"), (0, 0), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_max_straight() {
+        let row = grid("1111111111");
+        let limited = Pathfinder { max_straight: Some(3), ..Pathfinder::new(Enter) };
+        // going back a step breaks up the runs
+        let route = limited.route(&row, (0, 0), (9, 0)).unwrap();
+        assert_eq!(route.cost, 15);
+        assert_eq!(&route.path[..6], &[(0, 0), (1, 0), (2, 0), (3, 0), (2, 0), (3, 0)]);
+        assert_eq!(Pathfinder { max_straight: Some(9), ..limited.clone() }.route(&row, (0, 0), (9, 0)).unwrap().cost, 9);
+        let zigzag = Pathfinder { max_straight: Some(2), ..Pathfinder::new(Enter) };
+        let route = zigzag.route(&grid("11111\n11111"), (0, 0), (4, 0)).unwrap();
+        assert_eq!(route.cost, 6);
+        assert!(route.path.windows(4).all(|moves| moves[3].0 - moves[0].0 < 3));
+    }
+
+    #[test]
+    fn test_costs() {
+        let square = grid("111\n111\n111");
+        assert_eq!(Pathfinder::new(TurnPenalty(Enter, 10)).route(&square, (0, 0), (2, 2)).unwrap().cost, 14);
+        let corner = grid("19\n11");
+        assert_eq!(Pathfinder::new(Enter).route(&corner, (0, 0), (1, 0)).unwrap().cost, 9);
+        assert_eq!(Pathfinder::new(Exit).route(&corner, (0, 0), (1, 0)).unwrap().cost, 1);
+        let walls = Pathfinder::new(|grid: &Grid<u8>, step: &Step| (*grid.at(step.to) != 9).then_some(1));
+        let cave = grid("1911\n1919\n1119");
+        assert_eq!(walls.route(&cave, (0, 0), (3, 0)).unwrap().cost, 7);
+        assert_eq!(walls.route(&cave, (0, 0), (3, 2)), None);
+    }
+
+    #[test]
+    fn test_route_between() {
+        let cave = grid("1911\n1919\n1119");
+        let pathfinder = Pathfinder::new(Enter);
+        assert_eq!(pathfinder.route_between(&cave, &[(0, 0), (2, 0)], &[(3, 0)]), Some(Route { cost: 1, path: vec![(2, 0), (3, 0)] }));
+        assert_eq!(pathfinder.route_between(&cave, &[(0, 0)], &[(3, 2), (0, 2)]).unwrap().path, vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(pathfinder.route_between(&cave, &[], &[(3, 0)]), None);
+    }
+
+    #[test]
+    fn test_distances() {
+        let corner = grid("19\n11");
+        let distances = Pathfinder::new(Enter).distances(&corner, &[(0, 0)]);
+        assert_eq!(distances_to_csv(&distances), "0,9\n1,2\n");
+        let walls = Pathfinder::new(|grid: &Grid<u8>, step: &Step| (*grid.at(step.to) != 9).then_some(1));
+        assert_eq!(distances_to_csv(&walls.distances(&corner, &[(0, 0), (1, 1)])), "0,\n1,0\n");
+    }
+
+    #[test]
+    fn test_k_routes() {
+        let cave = grid("1912\n3115\n2431");
+        let pathfinder = Pathfinder::new(Enter);
+        let routes = pathfinder.k_routes(&cave, (0, 0), (3, 2), 10);
+        assert_eq!(routes[0], pathfinder.route(&cave, (0, 0), (3, 2)).unwrap());
+        // every loop-free path, by trying them all
+        fn walk(cave: &Grid<u8>, path: &mut Vec<Point>, cost: u64, costs: &mut Vec<u64>) {
+            let last = *path.last().unwrap();
+            if last == (3, 2) {
+                costs.push(cost);
+                return;
+            }
+            for next in cave.neighbors(last, Adjacency::Orthogonal).collect::<Vec<_>>() {
+                if !path.contains(&next) {
+                    path.push(next);
+                    walk(cave, path, cost + *cave.at(next) as u64, costs);
+                    path.pop();
+                }
+            }
+        }
+        let mut costs = vec![];
+        walk(&cave, &mut vec![(0, 0)], 0, &mut costs);
+        costs.sort();
+        assert_eq!(routes.iter().map(|route| route.cost).collect::<Vec<_>>(), costs[..10]);
+        for route in &routes {
+            assert_eq!(route.path.iter().collect::<HashSet<_>>().len(), route.path.len());
+            assert_eq!(route.cost, route.path[1..].iter().map(|point| *cave.at(*point) as u64).sum::<u64>());
+        }
+        assert_eq!(routes.iter().map(|route| &route.path).collect::<HashSet<_>>().len(), 10);
+        assert_eq!(pathfinder.k_routes(&grid("11\n11"), (0, 0), (1, 1), 5).len(), 2);
+        assert_eq!(pathfinder.k_routes(&grid("11\n11"), (0, 0), (1, 1), 0).len(), 0);
+        assert_eq!(Pathfinder { movement: Movement::Knight, ..pathfinder }.k_routes(&grid("11\n11"), (0, 0), (1, 1), 3).len(), 0);
+    }
+
+    #[test]
+    fn test_k_routes_max_straight() {
+        let limited = Pathfinder { max_straight: Some(3), ..Pathfinder::new(Enter) };
+        // the only way along the row goes back and forth, and there's no branching off it
+        let row = grid("1111111111");
+        let routes = limited.k_routes(&row, (0, 0), (9, 0), 4);
+        assert_eq!(routes, vec![limited.route(&row, (0, 0), (9, 0)).unwrap()]);
+        assert!(routes[0].path.iter().collect::<HashSet<_>>().len() < routes[0].path.len());
+        let routes = limited.k_routes(&grid("11111\n11111"), (0, 0), (4, 0), 6);
+        assert_eq!(routes.len(), 6);
+        assert_eq!(routes.iter().map(|route| &route.path).collect::<HashSet<_>>().len(), 6);
+        for route in &routes {
+            assert_eq!(route.cost, 6);
+            assert!(route.path.windows(5).all(|moves| moves[4].0 - moves[0].0 < 4));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use crate::solution::Answers;
+
+/// How the runner prints its results.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    /// `day 13, part 1: 17`, errors go to stderr.
+    #[default]
+    Text,
+    /// One JSON object per line for each requested day and part, errors included.
+    Json
+}
+
+/// Renders the result of running a day as one JSON object per requested part, e.g.
+/// `{"day":17,"part":1,"input":"./input/day_17.txt","answer":"9180","parse_ns":1200,"solve_ns":800,"error":null}`.
+/// `error` describes why the day or part couldn't be run, `answer` and the timings are `null` then.
+pub fn to_json(day: u8, parts: &[u8], input: &str, result: &Result<Answers, String>) -> Vec<String> {
+    parts
+        .iter()
+        .map(|part| {
+            let (answer, parse_time, solve_time, error) = match result {
+                Err(err) => (None, None, None, Some(err.clone())),
+                Ok(answers) => match answers.parts.iter().find(|answer| answer.part == *part) {
+                    Some(answer) => (Some(answer.value.as_str()), Some(answers.parse_time), Some(answer.time), None),
+                    None => (None, Some(answers.parse_time), None, Some(format!("part {part} is not available")))
+                }
+            };
+            format!("{{\"day\":{},\"part\":{},\"input\":{},\"answer\":{},\"parse_ns\":{},\"solve_ns\":{},\"error\":{}}}",
+                day, part, json_string(input), json_or_null(answer.map(json_string)),
+                json_or_null(parse_time.map(nanos)), json_or_null(solve_time.map(nanos)), json_or_null(error.as_deref().map(json_string)))
+        })
+        .collect()
+}
+
+fn nanos(duration: Duration) -> String {
+    duration.as_nanos().to_string()
+}
+
+fn json_or_null(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("null"))
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\r' => result += "\\r",
+            '\t' => result += "\\t",
+            c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c)
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a \"b\"\n\\c\u{1}"), "\"a \\\"b\\\"\\n\\\\c\\u0001\"");
+    }
+
+    #[test]
+    fn test_to_json() {
+        let answers = Answers {
+            parse_time: Duration::from_nanos(1200),
+            parts: vec![Answer { part: 1, value: String::from("507"), time: Duration::from_nanos(800) }]
+        };
+        assert_eq!(to_json(25, &[1, 2], "day_25.txt", &Ok(answers)), vec![
+            "{\"day\":25,\"part\":1,\"input\":\"day_25.txt\",\"answer\":\"507\",\"parse_ns\":1200,\"solve_ns\":800,\"error\":null}",
+            "{\"day\":25,\"part\":2,\"input\":\"day_25.txt\",\"answer\":null,\"parse_ns\":1200,\"solve_ns\":null,\"error\":\"part 2 is not available\"}"
+        ]);
+        let err = Err(String::from("couldn't read input"));
+        assert_eq!(to_json(13, &[1], "-", &err), vec![
+            "{\"day\":13,\"part\":1,\"input\":\"-\",\"answer\":null,\"parse_ns\":null,\"solve_ns\":null,\"error\":\"couldn't read input\"}"
+        ]);
+    }
+}
@@ -0,0 +1,65 @@
+use std::{fmt::Display, time::{Duration, Instant}};
+
+use crate::error::ParseError;
+
+/// A single day's puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+    /// Day 25 only has a single puzzle, every other day has two.
+    const HAS_PART_2: bool = true;
+    /// Part 2 is only solved when it's asked for on its own, for searches that don't finish.
+    const PART_2_ON_REQUEST: bool = false;
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_1(input: &Self::Input) -> Self::Part1;
+    fn part_2(input: &Self::Input) -> Self::Part2;
+}
+
+/// Rendered answer of a single part, with the time it took to solve it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Answer {
+    pub part: u8,
+    pub value: String,
+    pub time: Duration
+}
+
+/// Answers of the requested parts of a day, with the time it took to parse the input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Answers {
+    pub parse_time: Duration,
+    pub parts: Vec<Answer>
+}
+
+/// Answer of parts that have no puzzle to solve.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoPuzzle;
+
+impl Display for NoPuzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no puzzle")
+    }
+}
+
+/// Parses the day's input and solves the requested parts, rendering each answer to a string.
+pub fn solve<S: Solution>(input: &str, parts: &[u8]) -> Result<Answers, ParseError> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+    let parts = parts
+        .iter()
+        .filter_map(|part| {
+            let start = Instant::now();
+            let value = match part {
+                1 => S::part_1(&input).to_string(),
+                2 if S::HAS_PART_2 => S::part_2(&input).to_string(),
+                _ => return None
+            };
+            Some(Answer { part: *part, value, time: start.elapsed() })
+        })
+        .collect();
+    Ok(Answers { parse_time, parts })
+}
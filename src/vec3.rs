@@ -0,0 +1,196 @@
+//! Points and vectors in 3D space, and the rotations that map the axes onto each other.
+
+use std::{fmt::Display, ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign}};
+
+/// A point or vector with integer coordinates.
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+pub struct Vec3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0, 0, 0);
+
+    pub const fn new(x: i32, y: i32, z: i32) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    /// Sum of the absolute differences along each axis.
+    pub fn manhattan(self, other: Vec3) -> i32 {
+        let d = self - other;
+        d.x.abs() + d.y.abs() + d.z.abs()
+    }
+
+    /// Straight-line distance.
+    pub fn euclidean(self, other: Vec3) -> f64 {
+        let d = self - other;
+        ((d.x as f64).powi(2) + (d.y as f64).powi(2) + (d.z as f64).powi(2)).sqrt()
+    }
+
+    fn to_array(self) -> [i32; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl From<(i32, i32, i32)> for Vec3 {
+    fn from((x, y, z): (i32, i32, i32)) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl From<[i32; 3]> for Vec3 {
+    fn from([x, y, z]: [i32; 3]) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl Display for Vec3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i32> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, factor: i32) -> Vec3 {
+        Vec3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, other: Vec3) {
+        *self = *self - other;
+    }
+}
+
+/// One of the 24 rotations that turn each axis onto another axis, e.g. the way a scanner
+/// can be facing. Rotations form a group: they can be composed with `*` and inverted.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Rotation {
+    /// For each axis of the result, the axis of the input it's taken from and its sign.
+    axes: [(usize, i32); 3]
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation { axes: [(0, 1), (1, 1), (2, 1)] };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        permutations
+            .iter()
+            .flat_map(|axes| (0..8).map(move |signs| Rotation {
+                axes: [0, 1, 2].map(|i| (axes[i], if signs >> i & 1 == 0 { 1 } else { -1 }))
+            }))
+            .filter(|rotation| rotation.determinant() == 1)
+            .collect()
+    }
+
+    /// 1 for rotations, -1 for reflections, which can't be created from outside.
+    fn determinant(&self) -> i32 {
+        let [(a, _), (b, _), (c, _)] = self.axes;
+        let inversions = (a > b) as i32 + (a > c) as i32 + (b > c) as i32;
+        let parity = if inversions % 2 == 0 { 1 } else { -1 };
+        parity * self.axes.iter().map(|(_, sign)| sign).product::<i32>()
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        let v = v.to_array();
+        Vec3::from(self.axes.map(|(axis, sign)| sign * v[axis]))
+    }
+
+    /// The rotation that undoes this one.
+    pub fn inverse(&self) -> Rotation {
+        let mut axes = [(0, 1); 3];
+        for (i, (axis, sign)) in self.axes.iter().enumerate() {
+            axes[*axis] = (i, *sign);
+        }
+        Rotation { axes }
+    }
+}
+
+/// `a * b` rotates by `b` first and then by `a`, like multiplying rotation matrices.
+impl Mul for Rotation {
+    type Output = Rotation;
+
+    fn mul(self, other: Rotation) -> Rotation {
+        Rotation { axes: self.axes.map(|(axis, sign)| (other.axes[axis].0, sign * other.axes[axis].1)) }
+    }
+}
+
+impl Mul<Vec3> for Rotation {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.apply(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        let a = Vec3::new(1105, -1205, 1229);
+        let b = Vec3::new(-92, -2380, -20);
+        assert_eq!(a - b, Vec3::new(1197, 1175, 1249));
+        assert_eq!(a - b + b, a);
+        assert_eq!(-a * 2, Vec3::new(-2210, 2410, -2458));
+        assert_eq!(a.manhattan(b), 3621);
+        assert_eq!(Vec3::new(3, 4, 12).euclidean(Vec3::ZERO), 13.0);
+        assert_eq!(Vec3::from((1, 2, 3)).to_string(), "1,2,3");
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+        let v = Vec3::new(1, 2, 3);
+        assert_eq!(rotations.iter().map(|rotation| *rotation * v).collect::<HashSet<_>>().len(), 24);
+        for a in &rotations {
+            assert_eq!(*a * a.inverse(), Rotation::IDENTITY);
+            assert_eq!(a.inverse() * (*a * v), v);
+            for b in &rotations {
+                assert!(rotations.contains(&(*a * *b)));
+                assert_eq!((*a * *b) * v, *a * (*b * v));
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::{fs, io, time::{Duration, Instant}};
+
+use crate::days::{self, Selection};
+
+pub const DEFAULT_ANSWERS: &str = "./answers.txt";
+
+/// Known answer of one part of a day for a specific input file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    /// The solution came up with a different answer.
+    Mismatch(String),
+    /// The day couldn't be run at all, e.g. because its input is missing.
+    Fail(String)
+}
+
+/// One answer per line: `<day> <part> <input file> <answer>`. The answer is the rest of the
+/// line, with line breaks escaped as `\n` and backslashes as `\\`.
+pub fn parse_answers(contents: &str) -> Result<Vec<Expected>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let mut fields = line.splitn(4, ' ');
+            let (Some(day), Some(part), Some(input), Some(answer)) = (fields.next(), fields.next(), fields.next(), fields.next()) else {
+                return Err(format!("line {}: expected '<day> <part> <input file> <answer>'", i + 1));
+            };
+            let day = day.parse::<u8>().map_err(|_| format!("line {}: invalid day '{day}'", i + 1))?;
+            let part = match part {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(format!("line {}: invalid part '{part}', expected 1 or 2", i + 1))
+            };
+            Ok(Expected { day, part, input: String::from(input), answer: unescape(answer) })
+        })
+        .collect()
+}
+
+pub fn load_answers(path: &str) -> io::Result<Vec<Expected>> {
+    parse_answers(&fs::read_to_string(path)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn unescape(answer: &str) -> String {
+    let mut result = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => { result.push('\n'); chars.next(); },
+            ('\\', Some('\\')) => { result.push('\\'); chars.next(); },
+            _ => result.push(c)
+        }
+    }
+    result
+}
+
+/// Runs a day on the expected answer's input and compares the answer.
+pub fn check(expected: &Expected) -> (Outcome, Duration) {
+    let Some(day) = days::find(expected.day) else {
+        return (Outcome::Fail(format!("day {} is not available", expected.day)), Duration::ZERO);
+    };
+    let input = match fs::read_to_string(&expected.input) {
+        Ok(input) => input,
+        Err(err) => return (Outcome::Fail(format!("couldn't read input: {err}")), Duration::ZERO)
+    };
+    let start = Instant::now();
+    let answers = (day.solve)(&input, &[expected.part]);
+    let elapsed = start.elapsed();
+    let outcome = match answers {
+        Err(err) => Outcome::Fail(format!("couldn't parse input: {err}")),
+        Ok(answers) => match answers.parts.into_iter().next() {
+            Some(answer) if answer.value == expected.answer => Outcome::Pass,
+            Some(answer) => Outcome::Mismatch(answer.value),
+            None => Outcome::Fail(format!("day {:02} has no part {}", expected.day, expected.part))
+        }
+    };
+    (outcome, elapsed)
+}
+
+/// Checks all expected answers of the selected days and prints a report.
+/// Returns whether all of them passed.
+pub fn verify(answers_path: &str, selection: Selection) -> bool {
+    let answers = match load_answers(answers_path) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: couldn't read answers {answers_path}: {err}");
+            return false;
+        }
+    };
+    let selected: Vec<&Expected> = answers
+        .iter()
+        .filter(|expected| selection == Selection::All || selection == Selection::Day(expected.day))
+        .collect();
+    let (mut passed, mut mismatched, mut failed) = (0, 0, 0);
+    for expected in &selected {
+        let (outcome, elapsed) = check(expected);
+        let status = match outcome {
+            Outcome::Pass => {
+                passed += 1;
+                String::from("pass")
+            },
+            Outcome::Mismatch(answer) => {
+                mismatched += 1;
+                format!("MISMATCH, expected {:?}, got {:?}", expected.answer, answer)
+            },
+            Outcome::Fail(reason) => {
+                failed += 1;
+                format!("FAIL, {reason}")
+            }
+        };
+        println!("day {:02}, part {}, {}: {} ({:.2?})", expected.day, expected.part, expected.input, status, elapsed);
+    }
+    println!("{passed} passed, {mismatched} mismatched, {failed} failed");
+    passed == selected.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("# comment\n\n17 1 input/day_17.test.txt 45\n13 2 dots.txt #.#\\n.#.\\\\\n").unwrap();
+        assert_eq!(answers, vec![
+            Expected { day: 17, part: 1, input: String::from("input/day_17.test.txt"), answer: String::from("45") },
+            Expected { day: 13, part: 2, input: String::from("dots.txt"), answer: String::from("#.#\n.#.\\") }
+        ]);
+        assert!(parse_answers("17 3 input/day_17.test.txt 45").is_err());
+        assert!(parse_answers("17 1 input/day_17.test.txt").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let expected = |part, answer: &str| Expected { day: 17, part, input: String::from("./input/day_17.test.txt"), answer: String::from(answer) };
+        assert_eq!(check(&expected(1, "45")).0, Outcome::Pass);
+        assert_eq!(check(&expected(2, "113")).0, Outcome::Mismatch(String::from("112")));
+        let missing = Expected { input: String::from("./input/missing.txt"), ..expected(1, "45") };
+        assert!(matches!(check(&missing).0, Outcome::Fail(_)));
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let answers = load_answers(DEFAULT_ANSWERS).unwrap();
+        assert!(answers.iter().all(|expected| days::find(expected.day).is_some()));
+    }
+}